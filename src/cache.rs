@@ -0,0 +1,232 @@
+// src/cache.rs
+
+use rmcp::serde_json;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use crate::config::config;
+
+/// One enriched crate as it was returned, plus when it was stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    /// Cache key (crate name + the options that shaped the result).
+    #[serde(default)]
+    pub key: String,
+    pub crate_name: String,
+    pub version: String,
+    /// Unix seconds.
+    pub stored_at: i64,
+    pub value: serde_json::Value,
+    /// Size of the entry's file on disk (0 when not persisted).
+    #[serde(skip)]
+    pub size_bytes: u64,
+    /// The file the entry was loaded from or written to, so it is removed from there
+    /// even if `entry_file_name` has since changed.
+    #[serde(skip)]
+    pub path: Option<PathBuf>,
+}
+
+impl CacheEntry {
+    pub fn age_secs(&self) -> i64 {
+        (chrono::Utc::now().timestamp() - self.stored_at).max(0)
+    }
+}
+
+/// Enriched `query_rustdocs` results keyed by crate name + the options that shaped them.
+/// Mirrored to one JSON file per entry under `dir` so it survives restarts.
+#[derive(Debug, Default)]
+pub struct ResponseCache {
+    entries: RwLock<HashMap<String, CacheEntry>>,
+    dir: Option<PathBuf>,
+}
+
+static CACHE: OnceLock<ResponseCache> = OnceLock::new();
+
+/// The process-wide cache, loaded from disk on first use.
+pub fn cache() -> &'static ResponseCache {
+    CACHE.get_or_init(|| ResponseCache::load(config().cache_dir.clone()))
+}
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` its output is fixed, so file names stay the
+/// same across toolchain upgrades.
+fn stable_hash(s: &str) -> u64 {
+    s.bytes().fold(0xcbf2_9ce4_8422_2325, |h, b| (h ^ b as u64).wrapping_mul(0x0100_0000_01b3))
+}
+
+/// "{crate}-{version}-{hash of key}.json", safe as a file name on every platform.
+fn entry_file_name(entry: &CacheEntry) -> String {
+    let safe = |s: &str| -> String {
+        s.chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+            .collect()
+    };
+    format!("{}-{}-{:016x}.json", safe(&entry.crate_name), safe(&entry.version), stable_hash(&entry.key))
+}
+
+impl ResponseCache {
+    /// Read every entry file under `dir`; unreadable files are skipped and
+    /// expired/oversized entries evicted straight away.
+    pub fn load(dir: Option<PathBuf>) -> Self {
+        let mut map = HashMap::new();
+        if let Some(ref d) = dir {
+            if let Ok(read) = std::fs::read_dir(d) {
+                for file in read.flatten() {
+                    let path = file.path();
+                    if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                        continue;
+                    }
+                    let Ok(bytes) = std::fs::read(&path) else { continue };
+                    match serde_json::from_slice::<CacheEntry>(&bytes) {
+                        Ok(mut entry) if !entry.key.is_empty() => {
+                            entry.size_bytes = bytes.len() as u64;
+                            entry.path = Some(path.clone());
+                            // two files for one key (e.g. written under an older file name): keep the newer
+                            let stale = match map.get(&entry.key) {
+                                Some(prev) if prev.stored_at > entry.stored_at => Some(path.clone()),
+                                Some(prev) => prev.path.clone(),
+                                None => None,
+                            };
+                            if stale.as_ref() != Some(&path) {
+                                map.insert(entry.key.clone(), entry);
+                            }
+                            if let Some(stale) = stale {
+                                let _ = std::fs::remove_file(stale);
+                            }
+                        }
+                        _ => tracing::debug!(path = %path.display(), "skipping unreadable cache file"),
+                    }
+                }
+            }
+        }
+        let cache = ResponseCache { entries: RwLock::new(map), dir };
+        cache.evict();
+        if let Some(ref d) = cache.dir {
+            let n = cache.entries.read().unwrap_or_else(|e| e.into_inner()).len();
+            tracing::info!(dir = %d.display(), entries = n, "query_rustdocs cache loaded");
+        }
+        cache
+    }
+
+    pub fn get(&self, key: &str) -> Option<CacheEntry> {
+        self.entries.read().unwrap_or_else(|e| e.into_inner()).get(key).cloned()
+    }
+
+    /// Most recently stored entry for `crate_name`, whatever options produced it.
+    pub fn latest_for_crate(&self, crate_name: &str) -> Option<CacheEntry> {
+        self.entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .filter(|e| e.crate_name == crate_name)
+            .max_by_key(|e| e.stored_at)
+            .cloned()
+    }
+
+    pub fn put(&self, key: String, crate_name: &str, version: &str, value: serde_json::Value) {
+        let mut entry = CacheEntry {
+            key: key.clone(),
+            crate_name: crate_name.to_string(),
+            version: version.to_string(),
+            stored_at: chrono::Utc::now().timestamp(),
+            value,
+            size_bytes: 0,
+            path: None,
+        };
+        if let Some(ref d) = self.dir {
+            match self.write_entry(d, &entry) {
+                Ok((size, path)) => {
+                    entry.size_bytes = size;
+                    entry.path = Some(path);
+                }
+                Err(e) => tracing::warn!(dir = %d.display(), "failed to persist cache entry: {}", e),
+            }
+        }
+        let new_path = entry.path.clone();
+        let replaced = self.entries.write().unwrap_or_else(|e| e.into_inner()).insert(key, entry);
+        // a replaced entry's file goes too unless the new one was written over it
+        if let Some(old) = replaced.filter(|old| old.path.is_some() && old.path != new_path) {
+            self.remove_file(&old);
+        }
+        self.evict();
+    }
+
+    /// Every entry, sorted by crate name then newest first.
+    pub fn list(&self) -> Vec<CacheEntry> {
+        let mut out: Vec<CacheEntry> = self
+            .entries
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        out.sort_by(|a, b| a.crate_name.cmp(&b.crate_name).then(b.stored_at.cmp(&a.stored_at)));
+        out
+    }
+
+    /// Remove entries matching `pred` from memory and disk. Returns how many went.
+    pub fn remove_where(&self, pred: impl Fn(&CacheEntry) -> bool) -> usize {
+        let mut map = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let keys: Vec<String> = map.values().filter(|e| pred(e)).map(|e| e.key.clone()).collect();
+        for key in &keys {
+            if let Some(e) = map.remove(key) {
+                self.remove_file(&e);
+            }
+        }
+        keys.len()
+    }
+
+    fn write_entry(&self, dir: &Path, entry: &CacheEntry) -> std::io::Result<(u64, PathBuf)> {
+        std::fs::create_dir_all(dir)?;
+        let bytes = serde_json::to_vec(entry).map_err(std::io::Error::other)?;
+        // write then rename so a crash never leaves a half-written entry behind
+        let path = dir.join(entry_file_name(entry));
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, &bytes)?;
+        std::fs::rename(&tmp, &path)?;
+        Ok((bytes.len() as u64, path))
+    }
+
+    fn remove_file(&self, entry: &CacheEntry) {
+        let path = match (&entry.path, &self.dir) {
+            (Some(p), _) => p.clone(),
+            (None, Some(d)) => d.join(entry_file_name(entry)),
+            (None, None) => return,
+        };
+        let _ = std::fs::remove_file(path);
+    }
+
+    /// Drop entries past `MCP_CACHE_MAX_AGE_SECS`, then the oldest ones until the
+    /// persisted total fits in `MCP_CACHE_MAX_BYTES`.
+    fn evict(&self) {
+        let cfg = config();
+        let mut map = self.entries.write().unwrap_or_else(|e| e.into_inner());
+        let expired: Vec<String> = map
+            .values()
+            .filter(|e| e.age_secs() > cfg.cache_max_age_secs)
+            .map(|e| e.key.clone())
+            .collect();
+        for key in expired {
+            if let Some(e) = map.remove(&key) {
+                self.remove_file(&e);
+            }
+        }
+
+        let mut total: u64 = map.values().map(|e| e.size_bytes).sum();
+        if total <= cfg.cache_max_bytes {
+            return;
+        }
+        let mut by_age: Vec<(i64, String)> = map.values().map(|e| (e.stored_at, e.key.clone())).collect();
+        by_age.sort();
+        for (_, key) in by_age {
+            if total <= cfg.cache_max_bytes {
+                break;
+            }
+            if let Some(e) = map.remove(&key) {
+                total = total.saturating_sub(e.size_bytes);
+                self.remove_file(&e);
+            }
+        }
+    }
+}
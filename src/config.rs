@@ -0,0 +1,206 @@
+// src/config.rs
+
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Server-wide settings read from the environment (after `.env` is loaded).
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Config {
+    /// `MCP_CRATE_ALLOWLIST`: comma-separated glob patterns. When non-empty only
+    /// matching crates may be fetched.
+    pub crate_allowlist: Vec<String>,
+    /// `MCP_CRATE_DENYLIST`: comma-separated glob patterns that are never fetched.
+    /// Checked before the allowlist.
+    pub crate_denylist: Vec<String>,
+    /// `MCP_OFFLINE`: default for `query_rustdocs`'s `offline` arg.
+    pub offline: bool,
+    /// `MCP_CACHE_TTL_SECS`: how long a cached crate is served without refetching (default 3600).
+    pub cache_ttl_secs: i64,
+    /// `MCP_CACHE_DIR`: where cached crates are persisted (default `~/.cache/mcp-rustdocs`).
+    /// `None` when no home directory is known and the variable is unset.
+    pub cache_dir: Option<PathBuf>,
+    /// `MCP_CACHE_MAX_AGE_SECS`: entries older than this are evicted from disk (default 7 days).
+    pub cache_max_age_secs: i64,
+    /// `MCP_CACHE_MAX_BYTES`: total size cap for the on-disk cache (default 200 MB).
+    pub cache_max_bytes: u64,
+    /// `MCP_REGISTRY_BASE`: registry API base following crates.io's v1 shape
+    /// (default `https://crates.io/api/v1`).
+    pub registry_base: String,
+    /// `MCP_DOCS_BASE`: docs host with docs.rs's URL layout, for self-hosted rustdoc mirrors
+    /// (default `https://docs.rs`).
+    pub docs_base: String,
+    /// `MCP_MAX_CRATES`: crates enriched per `query_rustdocs` call; extras are dropped (default 25).
+    pub max_crates: usize,
+    /// `MCP_CRAWL_DELAY_MS`: minimum gap between docs.rs requests to the same host (default 100).
+    pub crawl_delay_ms: u64,
+    /// `MCP_CA_BUNDLE`: PEM file of extra root certificates, for networks that re-sign TLS.
+    pub ca_bundle: Option<PathBuf>,
+    /// `MCP_ALLOW_INVALID_CERTS`: skip TLS certificate verification entirely. Off by default.
+    pub allow_invalid_certs: bool,
+    /// `MCP_CONTENT_SELECTORS`: comma-separated CSS selectors for a docs page's main
+    /// content, most specific first (default `DEFAULT_CONTENT_SELECTORS`).
+    pub content_selectors: Vec<String>,
+    /// `MCP_RATE_REGISTRY`: registry API requests per second across all tools (default 1,
+    /// crates.io's crawler policy). 0 disables the limit.
+    pub rate_registry: u32,
+    /// `MCP_RATE_DOCS`: docs host requests per second across all tools (default 10). 0 disables the limit.
+    pub rate_docs: u32,
+    /// `MCP_RATE_GITHUB`: GitHub requests (API, pages and raw files) per second across all tools
+    /// (default 5). 0 disables the limit.
+    pub rate_github: u32,
+    /// `MCP_ENABLE_EXPERIMENTAL`: also register and advertise tools tagged experimental in main.rs.
+    pub enable_experimental: bool,
+    /// `MCP_DEFAULT_TZ`: IANA zone name (e.g. "Europe/Berlin") get_time reports in instead of
+    /// the system's local zone. Invalid names are logged and ignored.
+    pub default_tz: Option<String>,
+}
+
+/// Where a docs.rs page's main content lives with current rustdoc markup.
+pub const DEFAULT_CONTENT_SELECTORS: &[&str] = &["main", "div.content", "div#main", "article", "body"];
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// The process-wide config, read from the environment on first use.
+pub fn config() -> &'static Config {
+    CONFIG.get_or_init(Config::from_env)
+}
+
+fn env_list(key: &str) -> Vec<String> {
+    std::env::var(key)
+        .map(|v| {
+            v.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn env_num<T: std::str::FromStr>(key: &str, default: T) -> T {
+    std::env::var(key)
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(default)
+}
+
+fn default_cache_dir() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE"))?;
+    Some(PathBuf::from(home).join(".cache").join("mcp-rustdocs"))
+}
+
+fn env_bool(key: &str) -> bool {
+    std::env::var(key)
+        .map(|v| matches!(v.trim().to_lowercase().as_str(), "1" | "true" | "yes" | "on"))
+        .unwrap_or(false)
+}
+
+/// An IANA zone name from `key`, checked against the tz database.
+fn env_time_zone(key: &str) -> Option<String> {
+    let name = std::env::var(key).ok()?.trim().to_string();
+    if name.is_empty() {
+        return None;
+    }
+    match name.parse::<chrono_tz::Tz>() {
+        Ok(tz) => Some(tz.name().to_string()),
+        Err(_) => {
+            tracing::warn!(%name, "ignoring {}: not an IANA time zone name; using the local zone", key);
+            None
+        }
+    }
+}
+
+/// `*` matches any run of characters, `?` exactly one. Crate names compare
+/// case-insensitively with `-` and `_` treated alike, as crates.io does.
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    let norm = |s: &str| -> Vec<char> { s.to_lowercase().replace('-', "_").chars().collect() };
+    let (p, n) = (norm(pattern), norm(name));
+    let (mut pi, mut ni) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while ni < n.len() {
+        if pi < p.len() && (p[pi] == '?' || p[pi] == n[ni]) {
+            pi += 1;
+            ni += 1;
+        } else if pi < p.len() && p[pi] == '*' {
+            star = Some((pi, ni));
+            pi += 1;
+        } else if let Some((sp, sn)) = star {
+            pi = sp + 1;
+            ni = sn + 1;
+            star = Some((sp, sn + 1));
+        } else {
+            return false;
+        }
+    }
+    p[pi..].iter().all(|c| *c == '*')
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        Config {
+            crate_allowlist: env_list("MCP_CRATE_ALLOWLIST"),
+            crate_denylist: env_list("MCP_CRATE_DENYLIST"),
+            offline: env_bool("MCP_OFFLINE"),
+            cache_ttl_secs: env_num("MCP_CACHE_TTL_SECS", 3600),
+            cache_dir: std::env::var_os("MCP_CACHE_DIR")
+                .filter(|v| !v.is_empty())
+                .map(PathBuf::from)
+                .or_else(default_cache_dir),
+            cache_max_age_secs: env_num("MCP_CACHE_MAX_AGE_SECS", 7 * 24 * 3600),
+            cache_max_bytes: env_num("MCP_CACHE_MAX_BYTES", 200 * 1024 * 1024),
+            registry_base: std::env::var("MCP_REGISTRY_BASE")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| "https://crates.io/api/v1".to_string()),
+            docs_base: std::env::var("MCP_DOCS_BASE")
+                .ok()
+                .filter(|v| !v.trim().is_empty())
+                .unwrap_or_else(|| "https://docs.rs".to_string()),
+            max_crates: env_num("MCP_MAX_CRATES", 25).max(1),
+            crawl_delay_ms: env_num("MCP_CRAWL_DELAY_MS", 100),
+            ca_bundle: std::env::var_os("MCP_CA_BUNDLE").filter(|v| !v.is_empty()).map(PathBuf::from),
+            allow_invalid_certs: env_bool("MCP_ALLOW_INVALID_CERTS"),
+            content_selectors: Some(env_list("MCP_CONTENT_SELECTORS"))
+                .filter(|l| !l.is_empty())
+                .unwrap_or_else(|| DEFAULT_CONTENT_SELECTORS.iter().map(|s| s.to_string()).collect()),
+            rate_registry: env_num("MCP_RATE_REGISTRY", 1),
+            rate_docs: env_num("MCP_RATE_DOCS", 10),
+            rate_github: env_num("MCP_RATE_GITHUB", 5),
+            enable_experimental: env_bool("MCP_ENABLE_EXPERIMENTAL"),
+            default_tz: env_time_zone("MCP_DEFAULT_TZ"),
+        }
+    }
+
+    /// Apply `MCP_CA_BUNDLE` and `MCP_ALLOW_INVALID_CERTS` to an HTTP client being built.
+    /// A bundle that can't be read is logged and skipped, leaving default verification.
+    pub fn apply_tls(&self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        if let Some(ref path) = self.ca_bundle {
+            let certs = std::fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|pem| reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| e.to_string()));
+            match certs {
+                Ok(certs) => {
+                    for cert in certs {
+                        builder = builder.add_root_certificate(cert);
+                    }
+                }
+                Err(e) => tracing::warn!(path = %path.display(), "ignoring MCP_CA_BUNDLE: {}", e),
+            }
+        }
+        if self.allow_invalid_certs {
+            builder = builder.danger_accept_invalid_certs(true);
+        }
+        builder
+    }
+
+    /// `Err` with the reason when policy forbids fetching `crate_name`.
+    pub fn check_crate_allowed(&self, crate_name: &str) -> Result<(), String> {
+        if let Some(p) = self.crate_denylist.iter().find(|p| glob_matches(p, crate_name)) {
+            return Err(format!("crate '{}' is blocked by the server's crate denylist (pattern '{}')", crate_name, p));
+        }
+        if !self.crate_allowlist.is_empty() && !self.crate_allowlist.iter().any(|p| glob_matches(p, crate_name)) {
+            return Err(format!("crate '{}' is not on the server's crate allowlist", crate_name));
+        }
+        Ok(())
+    }
+}
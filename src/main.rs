@@ -14,17 +14,119 @@ use rmcp::{
     transport::stdio, ErrorData,
 };
 use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::Instrument;
+mod cache;
+mod config;
+mod metrics;
+mod rate_limit;
+mod request_id;
+mod state;
 mod tools;
 
+use state::AppState;
+
+/// Protocol revisions this server can speak, newest first.
+/// The first entry is what we advertise when the client asks for something we don't know.
+const SUPPORTED_PROTOCOL_VERSIONS: [ProtocolVersion; 2] = [
+    ProtocolVersion::V_2025_03_26,
+    ProtocolVersion::V_2024_11_05,
+];
+
+/// Echo the client's requested version if we support it, otherwise fall back to our newest.
+fn negotiate_protocol_version(requested: &ProtocolVersion) -> ProtocolVersion {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|v| *v == requested)
+        .cloned()
+        .unwrap_or_else(|| SUPPORTED_PROTOCOL_VERSIONS[0].clone())
+}
+
+/// How settled a tool's interface is. Experimental tools may still change shape
+/// and are only advertised when `MCP_ENABLE_EXPERIMENTAL` is set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stability {
+    Stable,
+    Experimental,
+}
+
+/// Every registered tool, in the order `get_info` lists them, with its stability.
+const TOOLS: &[(&str, Stability)] = &[
+    ("get_time", Stability::Stable),
+    ("boilerplate_example", Stability::Stable),
+    ("query_rustdocs", Stability::Stable),
+    ("ping", Stability::Stable),
+    ("metrics", Stability::Stable),
+    ("date_math", Stability::Stable),
+    ("recommend_crate", Stability::Stable),
+    ("cache_admin", Stability::Stable),
+    ("check_resolvable", Stability::Stable),
+    ("convert_units", Stability::Experimental),
+    ("version", Stability::Stable),
+    ("crate_feature_graph", Stability::Stable),
+    ("find_symbol", Stability::Stable),
+    ("docs_to_markdown", Stability::Stable),
+    ("examples_only", Stability::Stable),
+    ("browse_crates", Stability::Stable),
+    ("api_surface", Stability::Experimental),
+    ("latest_version", Stability::Stable),
+    ("crate_summary", Stability::Experimental),
+    ("std_docs", Stability::Experimental),
+    ("expand_crate_prefix", Stability::Stable),
+    ("diff_crate_api", Stability::Experimental),
+    ("normalize_crate_name", Stability::Stable),
+    ("fetch_repo_file", Stability::Stable),
+];
+
+/// Names of the tools this server exposes under the current config.
+fn enabled_tools() -> impl Iterator<Item = &'static str> {
+    let experimental = config::config().enable_experimental;
+    TOOLS
+        .iter()
+        .filter(move |(_, s)| experimental || *s == Stability::Stable)
+        .map(|(name, _)| *name)
+}
+
 #[derive(Clone)]
 pub struct MCPHandler {
     tool_router: ToolRouter<Self>,
+    state: Arc<AppState>,
+}
+
+impl MCPHandler {
+    /// Run a tool future and record its latency and outcome under `name`.
+    /// Each call gets a request id, attached to every log line it emits.
+    async fn tracked<F>(&self, name: &str, fut: F) -> Result<CallToolResult, ErrorData>
+    where
+        F: Future<Output = Result<CallToolResult, ErrorData>>,
+    {
+        let id = request_id::generate();
+        let span = tracing::info_span!("tool_call", tool = name, request_id = %id);
+        let started = Instant::now();
+        let result = request_id::scope(id, fut).instrument(span.clone()).await;
+        let failed = match &result {
+            Ok(r) => r.is_error.unwrap_or(false),
+            Err(_) => true,
+        };
+        let elapsed = started.elapsed();
+        tracing::debug!(parent: &span, elapsed_ms = elapsed.as_millis() as u64, failed, "tool call finished");
+        self.state.metrics.record(name, elapsed, failed);
+        result
+    }
 }
 
 #[tool_router]
 impl MCPHandler {
     pub fn new() -> Self {
-        Self { tool_router: Self::tool_router() }
+        let state = Arc::new(AppState::new());
+        let mut tool_router = Self::tool_router();
+        if !state.config.enable_experimental {
+            for (name, _) in TOOLS.iter().filter(|(_, s)| *s == Stability::Experimental) {
+                tool_router.remove_route(name);
+            }
+        }
+        Self { tool_router, state }
     }
 
     #[tool(name = "get_time", description = "Current timestamp in ms")]
@@ -32,7 +134,7 @@ impl MCPHandler {
         &self,
         args: rmcp::handler::server::tool::Parameters<tools::get_time::GetTimeArgs>,
     ) -> Result<CallToolResult, ErrorData> {
-        tools::get_time::get_time(args).await
+        self.tracked("get_time", tools::get_time::get_time(args)).await
     }
 
     #[tool(name = "boilerplate_example", description = "Echo parameter back")]
@@ -42,7 +144,11 @@ impl MCPHandler {
             tools::boilerplate_example::BoilerplateArgs
         >,
     ) -> Result<CallToolResult, ErrorData> {
-        tools::boilerplate_example::boilerplate_example(args).await
+        self.tracked(
+            "boilerplate_example",
+            tools::boilerplate_example::boilerplate_example(args),
+        )
+        .await
     }
     #[tool(name = "query_rustdocs", description = "figure out the crate to use based off the user's inquiry, then lookup the crates you intend to use and scrape the latest version and documentation, then use the updated crate version's documentation to write the code. do this for all crates you intend to use in the project.")]
     async fn query_rustdocs(
@@ -50,8 +156,179 @@ impl MCPHandler {
         args: rmcp::handler::server::tool::Parameters<
             tools::query_rustdocs::QueryRustDocsArgs
         >,
+        ctx: rmcp::service::RequestContext<rmcp::service::RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("query_rustdocs", tools::query_rustdocs::query_rustdocs(args, ctx, self.state.http.clone())).await
+    }
+
+    #[tool(name = "ping", description = "Health check: uptime, version, tool count and crates.io reachability")]
+    async fn ping(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::ping::PingArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        let tools_enabled = self.tool_router.list_all().len();
+        self.tracked(
+            "ping",
+            tools::ping::ping(args, self.state.started_at.elapsed(), tools_enabled, self.state.http.clone()),
+        )
+        .await
+    }
+
+    #[tool(name = "metrics", description = "Per-tool invocation, error and latency counters")]
+    async fn metrics(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::metrics::MetricsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("metrics", tools::metrics::metrics(args, self.state.metrics.clone())).await
+    }
+
+    #[tool(name = "date_math", description = "Add or subtract a duration from a datetime or now")]
+    async fn date_math(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::date_math::DateMathArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("date_math", tools::date_math::date_math(args)).await
+    }
+
+    #[tool(name = "recommend_crate", description = "Ranked crates.io shortlist for a described task")]
+    async fn recommend_crate(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::recommend_crate::RecommendCrateArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("recommend_crate", tools::recommend_crate::recommend_crate(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "cache_admin", description = "List or clear cached query_rustdocs results")]
+    async fn cache_admin(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::cache_admin::CacheAdminArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("cache_admin", tools::cache_admin::cache_admin(args, self.state.cache)).await
+    }
+
+    #[tool(name = "check_resolvable", description = "Check that dependency requirements resolve to non-yanked versions")]
+    async fn check_resolvable(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::check_resolvable::CheckResolvableArgs>,
     ) -> Result<CallToolResult, ErrorData> {
-        tools::query_rustdocs::query_rustdocs(args).await
+        self.tracked("check_resolvable", tools::check_resolvable::check_resolvable(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "convert_units", description = "Convert length, mass, temperature and data-size units")]
+    async fn convert_units(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::convert_units::ConvertUnitsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("convert_units", tools::convert_units::convert_units(args)).await
+    }
+
+    #[tool(name = "version", description = "Server version, git commit and build time")]
+    async fn version(&self) -> Result<CallToolResult, ErrorData> {
+        self.tracked("version", tools::version::version()).await
+    }
+
+    #[tool(name = "crate_feature_graph", description = "Feature dependency graph of a crate version")]
+    async fn crate_feature_graph(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::crate_feature_graph::CrateFeatureGraphArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("crate_feature_graph", tools::crate_feature_graph::crate_feature_graph(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "find_symbol", description = "Find which crates provide an item by name")]
+    async fn find_symbol(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::find_symbol::FindSymbolArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("find_symbol", tools::find_symbol::find_symbol(args)).await
+    }
+
+    #[tool(name = "docs_to_markdown", description = "Convert a docs.rs page to markdown")]
+    async fn docs_to_markdown(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::docs_to_markdown::DocsToMarkdownArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("docs_to_markdown", tools::docs_to_markdown::docs_to_markdown(args)).await
+    }
+
+    #[tool(name = "examples_only", description = "Fetch a crate's GitHub examples without crawling docs")]
+    async fn examples_only(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::examples_only::ExamplesOnlyArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("examples_only", tools::examples_only::examples_only(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "browse_crates", description = "List the most downloaded crates in a crates.io category or keyword")]
+    async fn browse_crates(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::browse_crates::BrowseCratesArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("browse_crates", tools::browse_crates::browse_crates(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "api_surface", description = "List a crate's public functions, types and traits as plain text")]
+    async fn api_surface(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::api_surface::ApiSurfaceArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("api_surface", tools::api_surface::api_surface(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "latest_version", description = "Latest versions and dependency lines for crates, registry only")]
+    async fn latest_version(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::latest_version::LatestVersionArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("latest_version", tools::latest_version::latest_version(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "crate_summary", description = "A few-sentence overview of a crate for deciding whether to use it")]
+    async fn crate_summary(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::crate_summary::CrateSummaryArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("crate_summary", tools::crate_summary::crate_summary(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "std_docs", description = "Standard library item or module docs from doc.rust-lang.org as markdown")]
+    async fn std_docs(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::std_docs::StdDocsArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("std_docs", tools::std_docs::std_docs(args)).await
+    }
+
+    #[tool(name = "expand_crate_prefix", description = "Published crates whose names start with a prefix, most downloaded first")]
+    async fn expand_crate_prefix(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::expand_crate_prefix::ExpandCratePrefixArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("expand_crate_prefix", tools::expand_crate_prefix::expand_crate_prefix(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "diff_crate_api", description = "Public API items added, removed and changed between two versions of a crate")]
+    async fn diff_crate_api(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::diff_crate_api::DiffCrateApiArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("diff_crate_api", tools::diff_crate_api::diff_crate_api(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "normalize_crate_name", description = "Canonical published name for a crate, or suggestions when it doesn't exist")]
+    async fn normalize_crate_name(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::normalize_crate_name::NormalizeCrateNameArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("normalize_crate_name", tools::normalize_crate_name::normalize_crate_name(args, self.state.http.clone())).await
+    }
+
+    #[tool(name = "fetch_repo_file", description = "One file from a crate's GitHub repository at its release tag")]
+    async fn fetch_repo_file(
+        &self,
+        args: rmcp::handler::server::tool::Parameters<tools::fetch_repo_file::FetchRepoFileArgs>,
+    ) -> Result<CallToolResult, ErrorData> {
+        self.tracked("fetch_repo_file", tools::fetch_repo_file::fetch_repo_file(args, self.state.http.clone())).await
     }
 }
 
@@ -59,31 +336,31 @@ impl MCPHandler {
 impl ServerHandler for MCPHandler {
     fn get_info(&self) -> ServerInfo {
         ServerInfo {
-            protocol_version: ProtocolVersion::V_2024_11_05,
+            protocol_version: SUPPORTED_PROTOCOL_VERSIONS[0].clone(),
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
                 .build(),
             server_info: Implementation {
                 name: "mcp-server".into(),
-                version: "0.1.0".into(),
+                version: env!("CARGO_PKG_VERSION").into(),
             },
-            instructions: Some("Tools: get_time, boilerplate_example".into()),
+            instructions: Some(format!("Tools: {}", enabled_tools().collect::<Vec<_>>().join(", "))),
         }
     }
 
     async fn initialize(
         &self,
-        _param: InitializeRequestParam,
+        param: InitializeRequestParam,
         _ctx: rmcp::service::RequestContext<rmcp::service::RoleServer>,
     ) -> Result<InitializeResult, ErrorData> {
         Ok(InitializeResult {
-            protocol_version: ProtocolVersion::V_2024_11_05,
+            protocol_version: negotiate_protocol_version(&param.protocol_version),
             capabilities: ServerCapabilities::builder()
                 .enable_tools()
                 .build(),
             server_info: Implementation {
                 name: "mcp-server".into(),
-                version: "0.1.0".into(),
+                version: env!("CARGO_PKG_VERSION").into(),
             },
             instructions: Some("Use tools via JSON-RPC".into()),
         })
@@ -136,6 +413,27 @@ impl ServerHandler for MCPHandler {
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
+    // stdout carries the MCP protocol, so logs go to stderr. Set RUST_LOG=debug for more.
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .with_writer(std::io::stderr)
+        .with_ansi(false)
+        .init();
+    let cfg = config::config();
+    if !cfg.crate_allowlist.is_empty() || !cfg.crate_denylist.is_empty() {
+        tracing::info!(allow = ?cfg.crate_allowlist, deny = ?cfg.crate_denylist, "crate policy active");
+    }
+    if cfg.allow_invalid_certs {
+        tracing::warn!("MCP_ALLOW_INVALID_CERTS is set: TLS certificates are NOT verified for any outgoing request");
+    }
+    if let Some(ref path) = cfg.ca_bundle {
+        tracing::info!(path = %path.display(), "trusting extra root certificates from MCP_CA_BUNDLE");
+    }
+    // load the persisted query_rustdocs cache up front rather than on the first call
+    cache::cache();
     let service = MCPHandler::new().serve(stdio()).await?;
     eprintln!("MCP server running on stdio…");
     service.waiting().await?;
@@ -0,0 +1,79 @@
+// src/metrics.rs
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Counters for a single tool. Updated lock-free once the entry exists.
+#[derive(Debug, Default)]
+struct ToolCounters {
+    invocations: AtomicU64,
+    errors: AtomicU64,
+    total_latency_us: AtomicU64,
+}
+
+/// Per-tool invocation/error/latency counters shared by every clone of the handler.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    tools: RwLock<HashMap<String, Arc<ToolCounters>>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ToolMetricsSnapshot {
+    pub tool: String,
+    pub invocations: u64,
+    pub errors: u64,
+    pub total_latency_ms: u64,
+    pub avg_latency_ms: f64,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn counters(&self, tool: &str) -> Arc<ToolCounters> {
+        if let Some(c) = self.tools.read().unwrap_or_else(|e| e.into_inner()).get(tool) {
+            return c.clone();
+        }
+        let mut map = self.tools.write().unwrap_or_else(|e| e.into_inner());
+        map.entry(tool.to_string()).or_default().clone()
+    }
+
+    /// Record one finished call of `tool`.
+    pub fn record(&self, tool: &str, elapsed: Duration, failed: bool) {
+        let c = self.counters(tool);
+        c.invocations.fetch_add(1, Ordering::Relaxed);
+        if failed {
+            c.errors.fetch_add(1, Ordering::Relaxed);
+        }
+        c.total_latency_us.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Current counters for every tool seen so far, sorted by tool name.
+    pub fn snapshot(&self) -> Vec<ToolMetricsSnapshot> {
+        let map = self.tools.read().unwrap_or_else(|e| e.into_inner());
+        let mut out: Vec<ToolMetricsSnapshot> = map
+            .iter()
+            .map(|(name, c)| {
+                let invocations = c.invocations.load(Ordering::Relaxed);
+                let total_us = c.total_latency_us.load(Ordering::Relaxed);
+                ToolMetricsSnapshot {
+                    tool: name.clone(),
+                    invocations,
+                    errors: c.errors.load(Ordering::Relaxed),
+                    total_latency_ms: total_us / 1000,
+                    avg_latency_ms: if invocations == 0 {
+                        0.0
+                    } else {
+                        total_us as f64 / invocations as f64 / 1000.0
+                    },
+                }
+            })
+            .collect();
+        out.sort_by(|a, b| a.tool.cmp(&b.tool));
+        out
+    }
+}
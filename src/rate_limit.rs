@@ -0,0 +1,57 @@
+// src/rate_limit.rs
+
+use governor::{DefaultDirectRateLimiter, Quota, RateLimiter};
+use std::num::NonZeroU32;
+use std::sync::OnceLock;
+
+/// Upstream services with their own request budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Upstream {
+    Registry,
+    Docs,
+    GitHub,
+}
+
+/// One limiter per upstream, shared by every tool. `None` when the rate is 0 (unlimited).
+fn limiter(upstream: Upstream) -> Option<&'static DefaultDirectRateLimiter> {
+    static REGISTRY: OnceLock<Option<DefaultDirectRateLimiter>> = OnceLock::new();
+    static DOCS: OnceLock<Option<DefaultDirectRateLimiter>> = OnceLock::new();
+    static GITHUB: OnceLock<Option<DefaultDirectRateLimiter>> = OnceLock::new();
+    let cfg = crate::config::config();
+    let (cell, rate) = match upstream {
+        Upstream::Registry => (&REGISTRY, cfg.rate_registry),
+        Upstream::Docs => (&DOCS, cfg.rate_docs),
+        Upstream::GitHub => (&GITHUB, cfg.rate_github),
+    };
+    cell.get_or_init(|| NonZeroU32::new(rate).map(|r| RateLimiter::direct(Quota::per_second(r))))
+        .as_ref()
+}
+
+fn host_of(url: &str) -> Option<String> {
+    reqwest::Url::parse(url).ok()?.host_str().map(|h| h.to_ascii_lowercase())
+}
+
+/// Which upstream `url` belongs to; `None` for hosts that aren't limited.
+fn classify(url: &str) -> Option<Upstream> {
+    let host = host_of(url)?;
+    let cfg = crate::config::config();
+    if host_of(&cfg.registry_base).as_deref() == Some(host.as_str())
+        || matches!(host.as_str(), "crates.io" | "static.crates.io")
+    {
+        Some(Upstream::Registry)
+    } else if host_of(&cfg.docs_base).as_deref() == Some(host.as_str()) || host == "doc.rust-lang.org" {
+        Some(Upstream::Docs)
+    } else if matches!(host.as_str(), "github.com" | "api.github.com" | "raw.githubusercontent.com") {
+        Some(Upstream::GitHub)
+    } else {
+        None
+    }
+}
+
+/// Wait until a request to `url` fits its upstream's budget (`MCP_RATE_REGISTRY`,
+/// `MCP_RATE_DOCS`, `MCP_RATE_GITHUB`). Call right before sending.
+pub async fn throttle(url: &str) {
+    if let Some(limiter) = classify(url).and_then(limiter) {
+        limiter.until_ready().await;
+    }
+}
@@ -0,0 +1,46 @@
+// src/state.rs
+
+use reqwest::Client;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::cache::ResponseCache;
+use crate::config::Config;
+use crate::metrics::Metrics;
+
+/// User agent for every outgoing request, so registries and docs.rs see one client.
+pub const USER_AGENT: &str = concat!("mcp-server/", env!("CARGO_PKG_VERSION"));
+
+/// Everything tools share, built once when the handler is created. The handler
+/// holds it in an `Arc`, so every clone (one per connection or request) sees the same state.
+pub struct AppState {
+    /// HTTP client for registry, docs and GitHub requests. Clones share one
+    /// connection pool, so keep-alive connections carry over between calls.
+    pub http: Client,
+    pub metrics: Arc<Metrics>,
+    pub cache: &'static ResponseCache,
+    pub config: &'static Config,
+    pub started_at: Instant,
+}
+
+impl AppState {
+    pub fn new() -> Self {
+        let config = crate::config::config();
+        let http = config
+            .apply_tls(Client::builder())
+            .user_agent(USER_AGENT)
+            .timeout(Duration::from_secs(18))
+            .build()
+            .unwrap_or_else(|e| {
+                tracing::warn!("failed to build the shared http client, using defaults: {}", e);
+                Client::new()
+            });
+        AppState {
+            http,
+            metrics: Arc::new(Metrics::new()),
+            cache: crate::cache::cache(),
+            config,
+            started_at: Instant::now(),
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// src/tools/api_surface.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::Deserialize;
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::tools::query_rustdocs::{
+    all_items_path, docs_base_from_url, extract_all_items_from_html, fetch_docs_page, fetch_rustdoc_json,
+    parse_docs_base, render_rustdoc_fn,
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ApiSurfaceArgs {
+    /// Crate whose public API to list.
+    #[schemars(example = "tokio")]
+    crate_name: String,
+
+    /// docs.rs version segment (default "latest").
+    #[serde(default)]
+    version: Option<String>,
+
+    /// Only items under this module, e.g. "sync" or "tokio::sync".
+    #[serde(default)]
+    #[schemars(example = "sync")]
+    module: Option<String>,
+
+    /// Maximum lines returned (default 1500).
+    #[serde(default)]
+    max_lines: Option<usize>,
+}
+
+/// Default `max_lines`.
+const DEFAULT_MAX_LINES: usize = 1500;
+
+/// rustdoc JSON ids are strings in older format versions and integers in newer ones.
+fn json_id(v: &serde_json::Value) -> String {
+    v.as_str().map(|s| s.to_string()).unwrap_or_else(|| v.to_string())
+}
+
+fn is_public(item: &serde_json::Value) -> bool {
+    matches!(item.get("visibility").and_then(|v| v.as_str()), Some("public") | Some("default"))
+}
+
+/// "<T, S>" from an item's generic parameters, lifetimes included.
+fn render_generics(inner: &serde_json::Value) -> String {
+    let names: Vec<&str> = inner
+        .get("generics")
+        .and_then(|g| g.get("params"))
+        .and_then(|p| p.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|p| p.get("name").and_then(|n| n.as_str()))
+        .filter(|n| !n.starts_with("impl "))
+        .collect();
+    if names.is_empty() { String::new() } else { format!("<{}>", names.join(", ")) }
+}
+
+/// (sort key, line) for every public item of the local crate. Methods are keyed
+/// under their type or trait so they sort right after its declaration.
+fn surface_from_json(doc: &serde_json::Value) -> Vec<(String, String)> {
+    let empty = serde_json::Map::new();
+    let index = doc.get("index").and_then(|i| i.as_object()).unwrap_or(&empty);
+    let paths = doc.get("paths").and_then(|p| p.as_object()).unwrap_or(&empty);
+    let path_of = |id: &str| -> Option<String> {
+        let entry = paths.get(id)?;
+        if entry.get("crate_id").and_then(|c| c.as_u64()) != Some(0) {
+            return None;
+        }
+        let segs: Vec<&str> = entry.get("path")?.as_array()?.iter().filter_map(|s| s.as_str()).collect();
+        (!segs.is_empty()).then(|| segs.join("::"))
+    };
+    // (name, function) for each public fn among `ids`
+    let methods = |ids: Option<&serde_json::Value>| -> Vec<(String, serde_json::Value)> {
+        ids.and_then(|i| i.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|id| index.get(&json_id(id)))
+            .filter(|m| is_public(m))
+            .filter_map(|m| {
+                let name = m.get("name")?.as_str()?.to_string();
+                let func = m.get("inner")?.get("function")?.clone();
+                Some((name, func))
+            })
+            .collect()
+    };
+
+    let mut out = Vec::new();
+    for (id, item) in index {
+        if item.get("crate_id").and_then(|c| c.as_u64()) != Some(0) || !is_public(item) {
+            continue;
+        }
+        let Some((kind, inner)) = item.get("inner").and_then(|i| i.as_object()).and_then(|o| o.iter().next()) else {
+            continue;
+        };
+        match kind.as_str() {
+            // methods have no `paths` entry; they are picked up through their impl or trait
+            "function" => {
+                if let Some(path) = path_of(id) {
+                    out.push((path.clone(), format!("pub {}", render_rustdoc_fn(&path, inner))));
+                }
+            }
+            "struct" | "enum" | "union" | "trait" | "type_alias" | "typedef" => {
+                let Some(path) = path_of(id) else { continue };
+                let keyword = match kind.as_str() {
+                    "type_alias" | "typedef" => "type",
+                    k => k,
+                };
+                out.push((path.clone(), format!("pub {} {}{}", keyword, path, render_generics(inner))));
+                if kind == "trait" {
+                    for (name, func) in methods(inner.get("items")) {
+                        out.push((format!("{}::{}", path, name), format!("    {}", render_rustdoc_fn(&name, &func))));
+                    }
+                }
+            }
+            "impl" => {
+                // inherent impls only; trait impls would repeat each trait's methods per type
+                if inner.get("trait").is_some_and(|t| !t.is_null()) {
+                    continue;
+                }
+                let Some(type_path) = inner
+                    .get("for")
+                    .and_then(|f| f.get("resolved_path"))
+                    .and_then(|p| p.get("id"))
+                    .and_then(|id| path_of(&json_id(id)))
+                else {
+                    continue;
+                };
+                for (name, func) in methods(inner.get("items")) {
+                    out.push((format!("{}::{}", type_path, name), format!("    pub {}", render_rustdoc_fn(&name, &func))));
+                }
+            }
+            "macro" | "proc_macro" => {
+                if let Some(path) = path_of(id) {
+                    out.push((path.clone(), format!("macro {}!", path)));
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Names only, from the crate's `all.html`, for builds without rustdoc JSON.
+fn surface_from_all_items(html: &str, lib: &str) -> Vec<(String, String)> {
+    extract_all_items_from_html(html)
+        .into_iter()
+        .map(|item| {
+            let path = all_items_path(lib, &item);
+            let line = format!("{} {}", item.kind, path);
+            (path, line)
+        })
+        .collect()
+}
+
+/// `Surface::source` when the items came from rustdoc JSON.
+pub(crate) const JSON_SOURCE: &str = "rustdoc JSON";
+/// `Surface::source` when only `all.html` was available.
+pub(crate) const HTML_SOURCE: &str = "all.html (no rustdoc JSON build; names only, no signatures)";
+
+/// A crate version's public items as (path, line) pairs, unsorted.
+pub(crate) struct Surface {
+    pub entries: Vec<(String, String)>,
+    /// `JSON_SOURCE` or `HTML_SOURCE`.
+    pub source: &'static str,
+    /// The version docs.rs resolved the request to.
+    pub version: String,
+}
+
+/// Public items of `crate_name` at `version` from rustdoc JSON, falling back to
+/// `all.html`. `html_only` skips the JSON attempt.
+pub(crate) async fn load_surface(
+    client: &Client,
+    docs_base: &str,
+    crate_name: &str,
+    version: &str,
+    html_only: bool,
+) -> Result<Surface, String> {
+    let lib = crate_name.replace('-', "_");
+    let json_err = if html_only {
+        "rustdoc JSON not requested".to_string()
+    } else {
+        match fetch_rustdoc_json(client, docs_base, crate_name, version, false).await {
+            Ok(doc) => {
+                let v = doc.get("crate_version").and_then(|v| v.as_str()).unwrap_or(version).to_string();
+                return Ok(Surface { entries: surface_from_json(&doc), source: JSON_SOURCE, version: v });
+            }
+            Err(e) => {
+                tracing::debug!(crate_name, version, "{}", e);
+                e
+            }
+        }
+    };
+    let delay = Duration::from_millis(crate::config::config().crawl_delay_ms);
+    let Some((final_url, html)) = fetch_docs_page(docs_base, crate_name, version, &format!("{}/all.html", lib), delay).await else {
+        return Err(format!("Could not fetch docs for '{}' {}: {}", crate_name, version, json_err));
+    };
+    let v = docs_base_from_url(&final_url)
+        .as_deref()
+        .and_then(|b| b.trim_end_matches('/').rsplit('/').next().map(|s| s.to_string()))
+        .unwrap_or_else(|| version.to_string());
+    Ok(Surface { entries: surface_from_all_items(&html, &lib), source: HTML_SOURCE, version: v })
+}
+
+#[tool(
+    name = "api_surface",
+    description = "Lists a crate's public API as plain text: one line per function signature, type, trait and macro, with each type's inherent methods and each trait's methods indented beneath it. Optionally limited to one module. Use it when you need the exact callable surface rather than a full query_rustdocs dump."
+)]
+pub async fn api_surface(
+    Parameters(args): Parameters<ApiSurfaceArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let crate_name = args.crate_name.trim().to_string();
+    if crate_name.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("crate_name is required.")]));
+    }
+    let cfg = crate::config::config();
+    if let Err(e) = cfg.check_crate_allowed(&crate_name) {
+        return Ok(CallToolResult::error(vec![Content::text(e)]));
+    }
+    let docs_base = match parse_docs_base(&cfg.docs_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let version = args.version.as_deref().map(str::trim).filter(|v| !v.is_empty()).unwrap_or("latest");
+    let lib = crate_name.replace('-', "_");
+
+    let Surface { mut entries, source, version: resolved_version } =
+        match load_surface(&client, &docs_base, &crate_name, version, false).await {
+            Ok(s) => s,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+    if let Some(module) = args.module.as_deref().map(str::trim).filter(|m| !m.is_empty()) {
+        let module = module
+            .trim_start_matches("crate::")
+            .trim_start_matches(&format!("{}::", lib))
+            .trim_matches(':');
+        let root = format!("{}::{}", lib, module);
+        let prefix = format!("{}::", root);
+        entries.retain(|(key, _)| *key == root || key.starts_with(&prefix));
+    }
+    entries.sort();
+    entries.dedup();
+
+    let total = entries.len();
+    let max_lines = args.max_lines.unwrap_or(DEFAULT_MAX_LINES).max(1);
+    let mut text = format!("// {} {} public API, from {}: {} lines", crate_name, resolved_version, source, total);
+    if total > max_lines {
+        text.push_str(&format!(" (showing the first {}; narrow with `module`)", max_lines));
+    }
+    text.push('\n');
+    for (_, line) in entries.into_iter().take(max_lines) {
+        text.push_str(&line);
+        text.push('\n');
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+}
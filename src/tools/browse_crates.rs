@@ -0,0 +1,174 @@
+// src/tools/browse_crates.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::future::Future;
+
+use crate::tools::query_rustdocs::{parse_registry_base, registry_get};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct BrowseCratesArgs {
+    /// crates.io category slug, e.g. "game-development" or "command-line-utilities".
+    #[serde(default)]
+    #[schemars(example = "game-development")]
+    category: Option<String>,
+
+    /// crates.io keyword, e.g. "ecs". Combined with `category` when both are given.
+    #[serde(default)]
+    #[schemars(example = "ecs")]
+    keyword: Option<String>,
+
+    /// Rank by downloads in the last 90 days instead of all-time downloads.
+    #[serde(default)]
+    recent: Option<bool>,
+
+    /// 1-based page (default 1).
+    #[serde(default)]
+    page: Option<usize>,
+
+    /// Crates per page (default 20, max 100).
+    #[serde(default)]
+    per_page: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct BrowsedCrate {
+    name: String,
+    description: Option<String>,
+    latest_version: Option<String>,
+    dependency_line: Option<String>,
+    downloads: u64,
+    recent_downloads: u64,
+    updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct BrowseCratesResponse {
+    category: Option<String>,
+    keyword: Option<String>,
+    sort: &'static str,
+    page: usize,
+    per_page: usize,
+    /// Crates matching the filter across all pages.
+    total: u64,
+    has_more: bool,
+    crates: Vec<BrowsedCrate>,
+}
+
+#[tool(
+    name = "browse_crates",
+    description = "Lists the most downloaded crates in a crates.io category (e.g. \"game-development\") or with a keyword (e.g. \"ecs\"), with paging. Use it to answer \"what are the popular crates for X\"."
+)]
+pub async fn browse_crates(
+    Parameters(args): Parameters<BrowseCratesArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let clean = |s: &Option<String>| s.as_deref().map(|v| v.trim().to_lowercase()).filter(|v| !v.is_empty());
+    let category = clean(&args.category);
+    let keyword = clean(&args.keyword);
+    if category.is_none() && keyword.is_none() {
+        return Ok(CallToolResult::error(vec![Content::text("Pass a `category` or a `keyword`.")]));
+    }
+    let registry_base = match parse_registry_base(&crate::config::config().registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let page = args.page.unwrap_or(1).max(1);
+    let per_page = args.per_page.unwrap_or(20).clamp(1, 100);
+    let sort = if args.recent.unwrap_or(false) { "recent-downloads" } else { "downloads" };
+    let mut query = vec![
+        ("sort", sort.to_string()),
+        ("page", page.to_string()),
+        ("per_page", per_page.to_string()),
+    ];
+    if let Some(ref c) = category {
+        query.push(("category", c.clone()));
+    }
+    if let Some(ref k) = keyword {
+        query.push(("keyword", k.clone()));
+    }
+
+    let what = match (&category, &keyword) {
+        (Some(c), Some(k)) => format!("category '{}' with keyword '{}'", c, k),
+        (Some(c), None) => format!("category '{}'", c),
+        (None, Some(k)) => format!("keyword '{}'", k),
+        (None, None) => unreachable!(),
+    };
+    let url = match reqwest::Url::parse_with_params(&format!("{}/crates", registry_base), &query) {
+        Ok(u) => u,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("invalid registry URL: {}", e))])),
+    };
+    let resp = match registry_get(&client, url.as_str(), &format!("crate listing for {}", what)).await {
+        Ok(r) => r,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+    };
+    if !resp.status().is_success() {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "registry returned {} listing crates for {}",
+            resp.status(),
+            what
+        ))]));
+    }
+    let v: serde_json::Value = match resp.json().await {
+        Ok(v) => v,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "invalid JSON from registry listing crates for {}: {}",
+                what, e
+            ))]))
+        }
+    };
+
+    let crates: Vec<BrowsedCrate> = v
+        .get("crates")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|c| {
+            let name = c.get("name")?.as_str()?.to_string();
+            let latest_version = c
+                .get("max_stable_version")
+                .and_then(|x| x.as_str())
+                .or_else(|| c.get("max_version").and_then(|x| x.as_str()))
+                .map(|s| s.to_string());
+            Some(BrowsedCrate {
+                description: c
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" ")),
+                dependency_line: latest_version.as_ref().map(|v| format!(r#"{} = "{}""#, name, v)),
+                latest_version,
+                downloads: c.get("downloads").and_then(|d| d.as_u64()).unwrap_or(0),
+                recent_downloads: c.get("recent_downloads").and_then(|d| d.as_u64()).unwrap_or(0),
+                updated_at: c.get("updated_at").and_then(|u| u.as_str()).map(|s| s.to_string()),
+                name,
+            })
+        })
+        .collect();
+    let total = v.pointer("/meta/total").and_then(|t| t.as_u64()).unwrap_or(crates.len() as u64);
+
+    let response = BrowseCratesResponse {
+        has_more: page.saturating_mul(per_page) < total as usize,
+        category,
+        keyword,
+        sort,
+        page,
+        per_page,
+        total,
+        crates,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
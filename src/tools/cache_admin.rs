@@ -0,0 +1,115 @@
+// src/tools/cache_admin.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use std::future::Future;
+
+use crate::cache::ResponseCache;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CacheAdminArgs {
+    /// "list", "clear" or "clear_crate".
+    #[schemars(description = "Subcommand: list, clear, or clear_crate")]
+    command: String,
+
+    /// Crate to purge for `clear_crate`; also filters `list`.
+    #[serde(default)]
+    #[schemars(description = "Crate name for clear_crate (optional filter for list)")]
+    crate_name: Option<String>,
+
+    /// Must be true for `clear`/`clear_crate` to delete anything.
+    #[serde(default)]
+    #[schemars(description = "Set true to confirm clear/clear_crate")]
+    confirm: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct CacheEntryInfo {
+    crate_name: String,
+    version: String,
+    age_secs: i64,
+    size_bytes: u64,
+    key: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CacheListResponse {
+    entries: Vec<CacheEntryInfo>,
+    total_entries: usize,
+    total_bytes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct CacheClearResponse {
+    command: String,
+    removed: usize,
+}
+
+fn to_payload<T: Serialize>(value: &T) -> Result<CallToolResult, ErrorData> {
+    let payload = serde_json::to_string_pretty(value)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
+
+#[tool(
+    name = "cache_admin",
+    description = "Inspect or purge the query_rustdocs cache. list shows cached crates (version, age, size); clear and clear_crate delete entries and require confirm: true."
+)]
+pub async fn cache_admin(
+    Parameters(args): Parameters<CacheAdminArgs>,
+    cache: &'static ResponseCache,
+) -> Result<CallToolResult, ErrorData> {
+    let command = args.command.trim().to_lowercase();
+    let crate_name = args.crate_name.as_deref().map(str::trim).filter(|s| !s.is_empty());
+    let confirmed = args.confirm.unwrap_or(false);
+
+    match command.as_str() {
+        "list" => {
+            let entries: Vec<CacheEntryInfo> = cache
+                .list()
+                .into_iter()
+                .filter(|e| crate_name.is_none_or(|n| e.crate_name == n))
+                .map(|e| CacheEntryInfo {
+                    age_secs: e.age_secs(),
+                    crate_name: e.crate_name,
+                    version: e.version,
+                    size_bytes: e.size_bytes,
+                    key: e.key,
+                })
+                .collect();
+            to_payload(&CacheListResponse {
+                total_entries: entries.len(),
+                total_bytes: entries.iter().map(|e| e.size_bytes).sum(),
+                entries,
+            })
+        }
+        "clear" | "clear_crate" if !confirmed => Ok(CallToolResult::error(vec![Content::text(format!(
+            "'{}' deletes cached data; call again with confirm: true to proceed.",
+            command
+        ))])),
+        "clear" => {
+            let removed = cache.remove_where(|_| true);
+            to_payload(&CacheClearResponse { command, removed })
+        }
+        "clear_crate" => {
+            let Some(name) = crate_name else {
+                return Ok(CallToolResult::error(vec![Content::text(
+                    "clear_crate needs crate_name.",
+                )]));
+            };
+            let removed = cache.remove_where(|e| e.crate_name == name);
+            to_payload(&CacheClearResponse { command, removed })
+        }
+        other => Ok(CallToolResult::error(vec![Content::text(format!(
+            "Unsupported command: '{}'. Use list, clear, or clear_crate.",
+            other
+        ))])),
+    }
+}
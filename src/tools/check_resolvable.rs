@@ -0,0 +1,260 @@
+// src/tools/check_resolvable.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use semver::{Version, VersionReq};
+use std::future::Future;
+
+use crate::tools::query_rustdocs::{collect_version_pages, parse_registry_base, registry_get};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CheckResolvableArgs {
+    /// Dependency specs, each like `tokio = "1.35"`, `serde = { version = "1", features = ["derive"] }`,
+    /// `rand@0.8` or `rand 0.8`.
+    #[serde(default)]
+    #[schemars(description = "Dependency specs, e.g. [\"tokio = \\\"1.35\\\"\", \"rand@0.8\"]")]
+    dependencies: Vec<String>,
+
+    /// A whole Cargo.toml; its [dependencies], [dev-dependencies] and [build-dependencies] are checked.
+    #[serde(default)]
+    #[schemars(description = "Optional Cargo.toml contents to check instead of / in addition to dependencies")]
+    manifest: Option<String>,
+}
+
+/// One requirement to check: crate name and the raw version requirement.
+#[derive(Debug, Clone)]
+struct DepSpec {
+    input: String,
+    name: String,
+    requirement: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SpecResult {
+    input: String,
+    name: String,
+    requirement: String,
+    satisfiable: bool,
+    /// Newest non-yanked version matching the requirement.
+    resolved_version: Option<String>,
+    /// Newest non-yanked version overall, offered when the requirement can't be met.
+    closest_available: Option<String>,
+    note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckResolvableResponse {
+    all_satisfiable: bool,
+    results: Vec<SpecResult>,
+}
+
+/// Version requirement from a TOML dependency value: `"1.2"` or `{ version = "1.2", ... }`.
+/// Path/git dependencies without a version come back as `None`.
+fn requirement_from_toml(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(s) => Some(s.clone()),
+        toml::Value::Table(t) => t.get("version").and_then(|v| v.as_str()).map(|s| s.to_string()),
+        _ => None,
+    }
+}
+
+/// Parse one free-form spec line into (name, requirement).
+fn parse_spec(raw: &str) -> Result<DepSpec, String> {
+    let line = raw.trim();
+    let spec = |name: &str, req: &str| DepSpec {
+        input: raw.to_string(),
+        name: name.trim().trim_matches('"').to_string(),
+        requirement: req.trim().to_string(),
+    };
+    // a Cargo.toml line only when it parses as one: `serde >= 1.0` and `rand@=0.8.5` contain '=' too
+    if let Ok(doc) = line.parse::<toml::Table>() {
+        if let Some((name, value)) = doc.iter().next() {
+            let req = requirement_from_toml(value)
+                .ok_or_else(|| format!("'{}' has no version requirement (path/git dependencies can't be checked)", raw))?;
+            return Ok(spec(name, &req));
+        }
+    }
+    if let Some((name, req)) = line.split_once('@') {
+        return Ok(spec(name, req));
+    }
+    match line.split_once(char::is_whitespace) {
+        Some((name, req)) => Ok(spec(name, req)),
+        None if !line.is_empty() => Ok(spec(line, "*")),
+        None => Err("empty dependency spec".to_string()),
+    }
+}
+
+/// Every versioned dependency in a Cargo.toml's dependency tables.
+fn specs_from_manifest(manifest: &str) -> Result<Vec<DepSpec>, String> {
+    let doc: toml::Table = manifest.parse().map_err(|e| format!("could not parse manifest: {}", e))?;
+    let mut out = Vec::new();
+    for table in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        let Some(deps) = doc.get(table).and_then(|d| d.as_table()) else { continue };
+        for (key, value) in deps {
+            let Some(req) = requirement_from_toml(value) else { continue };
+            // `alias = { package = "real-name", version = "..." }`
+            let name = value
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or(key)
+                .to_string();
+            out.push(DepSpec {
+                input: format!("[{}] {} = \"{}\"", table, key, req),
+                name,
+                requirement: req,
+            });
+        }
+    }
+    Ok(out)
+}
+
+/// (version, yanked) for every published version of `name`, following `meta.next_page`.
+pub(crate) async fn fetch_versions(client: &Client, registry_base: &str, name: &str) -> Result<Vec<(Version, bool)>, String> {
+    let url = format!("{}/crates/{}/versions", registry_base, name);
+    let resp = registry_get(client, &url, &format!("versions for '{}'", name))
+        .await
+        .map_err(|e| e.to_string())?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("crate '{}' does not exist on the registry", name));
+    }
+    if !resp.status().is_success() {
+        return Err(format!("registry returned {} for '{}'", resp.status(), name));
+    }
+    let first: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("invalid JSON from registry for '{}': {}", name, e))?;
+    let versions = collect_version_pages(client, &url, &first, name)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(versions
+        .iter()
+        .filter_map(|ver| {
+            let num = Version::parse(ver.get("num")?.as_str()?).ok()?;
+            let yanked = ver.get("yanked").and_then(|y| y.as_bool()).unwrap_or(false);
+            Some((num, yanked))
+        })
+        .collect())
+}
+
+async fn check_spec(client: &Client, registry_base: &str, spec: DepSpec) -> SpecResult {
+    let mut result = SpecResult {
+        input: spec.input,
+        name: spec.name,
+        requirement: spec.requirement,
+        satisfiable: false,
+        resolved_version: None,
+        closest_available: None,
+        note: None,
+    };
+    if let Err(e) = crate::config::config().check_crate_allowed(&result.name) {
+        result.note = Some(e);
+        return result;
+    }
+    let req = match VersionReq::parse(&result.requirement) {
+        Ok(r) => r,
+        Err(e) => {
+            result.note = Some(format!("invalid version requirement: {}", e));
+            return result;
+        }
+    };
+    let versions = match fetch_versions(client, registry_base, &result.name).await {
+        Ok(v) => v,
+        Err(e) => {
+            result.note = Some(e);
+            return result;
+        }
+    };
+
+    let newest_matching = |yanked_ok: bool| {
+        versions
+            .iter()
+            .filter(|(v, y)| (yanked_ok || !*y) && req.matches(v))
+            .map(|(v, _)| v)
+            .max()
+    };
+    if let Some(v) = newest_matching(false) {
+        result.satisfiable = true;
+        result.resolved_version = Some(v.to_string());
+        return result;
+    }
+
+    // prefer a stable release as the suggestion, fall back to any live version
+    let live = versions.iter().filter(|(_, y)| !*y).map(|(v, _)| v);
+    result.closest_available = live
+        .clone()
+        .filter(|v| v.pre.is_empty())
+        .max()
+        .or_else(|| live.max())
+        .map(|v| v.to_string());
+    result.note = Some(match newest_matching(true) {
+        Some(v) => format!("only yanked versions match (newest: {})", v),
+        None if versions.is_empty() => "no published versions".to_string(),
+        None => "no published version matches the requirement".to_string(),
+    });
+    result
+}
+
+#[tool(
+    name = "check_resolvable",
+    description = "Checks dependency specs (or a whole Cargo.toml) against the registry: for each, whether a non-yanked version satisfies the requirement, which one, and the closest available version when none does."
+)]
+pub async fn check_resolvable(
+    Parameters(args): Parameters<CheckResolvableArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let mut specs = Vec::new();
+    let mut results = Vec::new();
+    for raw in &args.dependencies {
+        match parse_spec(raw) {
+            Ok(s) => specs.push(s),
+            Err(e) => results.push(SpecResult {
+                input: raw.clone(),
+                name: String::new(),
+                requirement: String::new(),
+                satisfiable: false,
+                resolved_version: None,
+                closest_available: None,
+                note: Some(e),
+            }),
+        }
+    }
+    if let Some(ref manifest) = args.manifest {
+        match specs_from_manifest(manifest) {
+            Ok(s) => specs.extend(s),
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        }
+    }
+    if specs.is_empty() && results.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text(
+            "No dependency specs provided. Pass `dependencies` or `manifest`.",
+        )]));
+    }
+
+    let registry_base = match parse_registry_base(&crate::config::config().registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    for spec in specs {
+        results.push(check_spec(&client, &registry_base, spec).await);
+    }
+
+    let response = CheckResolvableResponse {
+        all_satisfiable: results.iter().all(|r| r.satisfiable),
+        results,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
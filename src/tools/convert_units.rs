@@ -0,0 +1,237 @@
+// src/tools/convert_units.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use serde::Deserialize;
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+
+use std::future::Future;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ConvertUnitsArgs {
+    /// Number to convert.
+    #[schemars(description = "Value to convert", example = 5.0)]
+    value: f64,
+
+    /// Unit of `value`, e.g. "km", "lb", "C", "MiB".
+    #[schemars(description = "Unit to convert from, e.g. km, lb, C, MiB", example = "km")]
+    from: String,
+
+    /// Target unit in the same category, e.g. "mi", "kg", "F", "MB".
+    #[schemars(description = "Unit to convert to, e.g. mi, kg, F, MB", example = "mi")]
+    to: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Category {
+    Length,
+    Mass,
+    Temperature,
+    DataSize,
+}
+
+impl Category {
+    fn name(self) -> &'static str {
+        match self {
+            Category::Length => "length",
+            Category::Mass => "mass",
+            Category::Temperature => "temperature",
+            Category::DataSize => "data size",
+        }
+    }
+}
+
+/// A unit: the names it answers to, its category, and how many base units one of it is
+/// (metres, kilograms, bytes). Temperatures convert through Celsius instead.
+#[derive(Debug)]
+struct Unit {
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    category: Category,
+    factor: f64,
+}
+
+const UNITS: &[Unit] = &[
+    // length, base metre
+    Unit { symbol: "mm", aliases: &["mm", "millimeter", "millimeters", "millimetre", "millimetres"], category: Category::Length, factor: 0.001 },
+    Unit { symbol: "cm", aliases: &["cm", "centimeter", "centimeters", "centimetre", "centimetres"], category: Category::Length, factor: 0.01 },
+    Unit { symbol: "m", aliases: &["m", "meter", "meters", "metre", "metres"], category: Category::Length, factor: 1.0 },
+    Unit { symbol: "km", aliases: &["km", "kilometer", "kilometers", "kilometre", "kilometres"], category: Category::Length, factor: 1000.0 },
+    Unit { symbol: "in", aliases: &["in", "inch", "inches", "\""], category: Category::Length, factor: 0.0254 },
+    Unit { symbol: "ft", aliases: &["ft", "foot", "feet", "'"], category: Category::Length, factor: 0.3048 },
+    Unit { symbol: "yd", aliases: &["yd", "yard", "yards"], category: Category::Length, factor: 0.9144 },
+    Unit { symbol: "mi", aliases: &["mi", "mile", "miles"], category: Category::Length, factor: 1609.344 },
+    Unit { symbol: "nmi", aliases: &["nmi", "nautical mile", "nautical miles"], category: Category::Length, factor: 1852.0 },
+    // mass, base kilogram
+    Unit { symbol: "mg", aliases: &["mg", "milligram", "milligrams"], category: Category::Mass, factor: 1e-6 },
+    Unit { symbol: "g", aliases: &["g", "gram", "grams"], category: Category::Mass, factor: 0.001 },
+    Unit { symbol: "kg", aliases: &["kg", "kilogram", "kilograms", "kilo", "kilos"], category: Category::Mass, factor: 1.0 },
+    Unit { symbol: "t", aliases: &["t", "tonne", "tonnes", "metric ton", "metric tons"], category: Category::Mass, factor: 1000.0 },
+    Unit { symbol: "oz", aliases: &["oz", "ounce", "ounces"], category: Category::Mass, factor: 0.028349523125 },
+    Unit { symbol: "lb", aliases: &["lb", "lbs", "pound", "pounds"], category: Category::Mass, factor: 0.45359237 },
+    Unit { symbol: "st", aliases: &["st", "stone", "stones"], category: Category::Mass, factor: 6.35029318 },
+    // temperature, handled separately
+    Unit { symbol: "°C", aliases: &["C", "°C", "celsius", "centigrade"], category: Category::Temperature, factor: 1.0 },
+    Unit { symbol: "°F", aliases: &["F", "°F", "fahrenheit"], category: Category::Temperature, factor: 1.0 },
+    Unit { symbol: "K", aliases: &["K", "kelvin", "kelvins"], category: Category::Temperature, factor: 1.0 },
+    // data size, base byte; "b" is a bit and "B" a byte, so these are matched case-sensitively first
+    Unit { symbol: "bit", aliases: &["bit", "bits", "b"], category: Category::DataSize, factor: 0.125 },
+    Unit { symbol: "B", aliases: &["B", "byte", "bytes"], category: Category::DataSize, factor: 1.0 },
+    Unit { symbol: "kB", aliases: &["kB", "KB", "kilobyte", "kilobytes"], category: Category::DataSize, factor: 1e3 },
+    Unit { symbol: "MB", aliases: &["MB", "megabyte", "megabytes"], category: Category::DataSize, factor: 1e6 },
+    Unit { symbol: "GB", aliases: &["GB", "gigabyte", "gigabytes"], category: Category::DataSize, factor: 1e9 },
+    Unit { symbol: "TB", aliases: &["TB", "terabyte", "terabytes"], category: Category::DataSize, factor: 1e12 },
+    Unit { symbol: "KiB", aliases: &["KiB", "kibibyte", "kibibytes"], category: Category::DataSize, factor: 1024.0 },
+    Unit { symbol: "MiB", aliases: &["MiB", "mebibyte", "mebibytes"], category: Category::DataSize, factor: 1048576.0 },
+    Unit { symbol: "GiB", aliases: &["GiB", "gibibyte", "gibibytes"], category: Category::DataSize, factor: 1073741824.0 },
+    Unit { symbol: "TiB", aliases: &["TiB", "tebibyte", "tebibytes"], category: Category::DataSize, factor: 1099511627776.0 },
+    Unit { symbol: "kbit", aliases: &["kbit", "Kbit", "kb", "Kb", "kilobit", "kilobits"], category: Category::DataSize, factor: 125.0 },
+    Unit { symbol: "Mbit", aliases: &["Mbit", "Mb", "megabit", "megabits"], category: Category::DataSize, factor: 125e3 },
+    Unit { symbol: "Gbit", aliases: &["Gbit", "Gb", "gigabit", "gigabits"], category: Category::DataSize, factor: 125e6 },
+];
+
+const SUPPORTED_UNITS: &str = "length (mm, cm, m, km, in, ft, yd, mi, nmi), mass (mg, g, kg, t, oz, lb, st), temperature (C, F, K), data size (bit, B, kB, MB, GB, TB, KiB, MiB, GiB, TiB, kbit, Mbit, Gbit)";
+
+/// Exact alias match first (so "Mb" and "MB" stay distinct), then a case-insensitive
+/// match. A case-insensitive name that fits several units (e.g. "mb") is an error
+/// naming the candidates rather than a guess.
+fn find_unit(name: &str) -> Result<&'static Unit, String> {
+    let name = name.trim();
+    if let Some(u) = UNITS.iter().find(|u| u.aliases.contains(&name)) {
+        return Ok(u);
+    }
+    let lower = name.to_lowercase();
+    let matches: Vec<&'static Unit> = UNITS
+        .iter()
+        .filter(|u| u.aliases.iter().any(|a| a.to_lowercase() == lower))
+        .collect();
+    match matches.as_slice() {
+        [] => Err(format!("Unknown unit: '{}'. Supported: {}.", name, SUPPORTED_UNITS)),
+        [unit] => Ok(*unit),
+        candidates => {
+            let symbols: Vec<&str> = candidates.iter().map(|u| u.symbol).collect();
+            Err(format!(
+                "Ambiguous unit: '{}', did you mean {}?",
+                name,
+                symbols.join(" or ")
+            ))
+        }
+    }
+}
+
+fn to_celsius(value: f64, unit: &Unit) -> f64 {
+    match unit.symbol {
+        "°F" => (value - 32.0) * 5.0 / 9.0,
+        "K" => value - 273.15,
+        _ => value,
+    }
+}
+
+fn from_celsius(value: f64, unit: &Unit) -> f64 {
+    match unit.symbol {
+        "°F" => value * 9.0 / 5.0 + 32.0,
+        "K" => value + 273.15,
+        _ => value,
+    }
+}
+
+/// Up to 6 decimal places, trailing zeros dropped; very large/small values in scientific notation.
+fn format_value(v: f64) -> String {
+    let abs = v.abs();
+    if abs != 0.0 && !(1e-6..1e15).contains(&abs) {
+        return format!("{:e}", v);
+    }
+    let s = format!("{:.6}", v);
+    let s = s.trim_end_matches('0').trim_end_matches('.');
+    if s == "-0" { "0".to_string() } else { s.to_string() }
+}
+
+#[tool(
+    name = "convert_units",
+    description = "Converts a value between units of length (mm, m, km, in, ft, mi, ...), mass (g, kg, oz, lb, ...), temperature (C, F, K) and data size (B, kB, MB, KiB, MiB, bit, Mbit, ...)."
+)]
+pub async fn convert_units(
+    Parameters(args): Parameters<ConvertUnitsArgs>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    if !args.value.is_finite() {
+        return Ok(CallToolResult::error(vec![Content::text("Value must be a finite number.")]));
+    }
+    let (from, to) = match (find_unit(&args.from), find_unit(&args.to)) {
+        (Ok(from), Ok(to)) => (from, to),
+        (Err(e), _) | (_, Err(e)) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+    if from.category != to.category {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "Cannot convert {} ({}) to {} ({}): different unit categories.",
+            from.symbol,
+            from.category.name(),
+            to.symbol,
+            to.category.name()
+        ))]));
+    }
+
+    let result = match from.category {
+        Category::Temperature => from_celsius(to_celsius(args.value, from), to),
+        _ => args.value * from.factor / to.factor,
+    };
+    if from.category == Category::Temperature && to_celsius(args.value, from) < -273.15 {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "{} {} is below absolute zero.",
+            format_value(args.value),
+            from.symbol
+        ))]));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(format!(
+        "{} {} = {} {}",
+        format_value(args.value),
+        from.symbol,
+        format_value(result),
+        to.symbol
+    ))]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_unit_matches_exact_then_case_insensitive() {
+        assert_eq!(find_unit("MB").unwrap().symbol, "MB");
+        assert_eq!(find_unit("Mb").unwrap().symbol, "Mbit");
+        assert_eq!(find_unit(" KILOMETRES ").unwrap().symbol, "km");
+        assert_eq!(find_unit("mib").unwrap().symbol, "MiB");
+    }
+
+    #[test]
+    fn find_unit_reports_ambiguous_and_unknown_names() {
+        let err = find_unit("mb").unwrap_err();
+        assert!(err.starts_with("Ambiguous unit: 'mb'"), "{}", err);
+        assert!(err.contains("MB or Mbit"), "{}", err);
+        assert!(find_unit("furlong").unwrap_err().starts_with("Unknown unit: 'furlong'"));
+    }
+
+    #[test]
+    fn temperature_round_trips_through_celsius() {
+        let f = find_unit("F").unwrap();
+        let k = find_unit("K").unwrap();
+        assert!((from_celsius(100.0, f) - 212.0).abs() < 1e-9);
+        assert!((to_celsius(212.0, f) - 100.0).abs() < 1e-9);
+        for v in [-40.0, 0.0, 37.5, 1000.0] {
+            assert!((to_celsius(from_celsius(v, f), f) - v).abs() < 1e-9);
+            assert!((to_celsius(from_celsius(v, k), k) - v).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn format_value_trims_and_switches_to_scientific() {
+        assert_eq!(format_value(1.5), "1.5");
+        assert_eq!(format_value(2.0), "2");
+        assert_eq!(format_value(0.1234567), "0.123457");
+        assert_eq!(format_value(-0.0000001), "-1e-7");
+        assert_eq!(format_value(-0.0), "0");
+        assert_eq!(format_value(1e15), "1e15");
+    }
+}
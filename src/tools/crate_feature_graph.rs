@@ -0,0 +1,318 @@
+// src/tools/crate_feature_graph.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::Write as _;
+use std::future::Future;
+
+use crate::tools::check_resolvable::fetch_versions;
+use crate::tools::query_rustdocs::{parse_registry_base, registry_get};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateFeatureGraphArgs {
+    /// Crate to inspect.
+    #[schemars(description = "Crate name", example = "tokio")]
+    crate_name: String,
+
+    /// Exact version or a requirement like "1.35"; the newest stable release when omitted.
+    #[serde(default)]
+    #[schemars(description = "Version or version requirement (default: latest stable)", example = "1")]
+    version: Option<String>,
+}
+
+/// One feature and everything turning it on enables.
+#[derive(Debug, Serialize)]
+struct FeatureNode {
+    name: String,
+    /// Other features of this crate.
+    enables_features: Vec<String>,
+    /// Optional dependencies pulled in (`dep:x`, `x/feat`, or the implicit feature `x`).
+    enables_dependencies: Vec<String>,
+    /// Features of dependencies, as written (`serde/derive`, `tokio?/rt`).
+    dependency_features: Vec<String>,
+    /// True for the feature Cargo creates for an optional dependency never named with `dep:`.
+    implicit: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct FeatureGraphResponse {
+    crate_name: String,
+    version: String,
+    default_features: Vec<String>,
+    optional_dependencies: Vec<String>,
+    /// Adjacency list, sorted by feature name.
+    features: Vec<FeatureNode>,
+    /// The same graph as an indented tree rooted at features nothing else enables.
+    tree: String,
+    /// Why the graph may be partial, e.g. the dependency list could not be fetched.
+    warnings: Vec<String>,
+}
+
+/// (name, optional) for each normal/build/dev dependency of one version.
+async fn fetch_dependencies(client: &Client, registry_base: &str, name: &str, version: &str) -> Result<Vec<(String, bool)>, String> {
+    let url = format!("{}/crates/{}/{}/dependencies", registry_base, name, version);
+    let resp = registry_get(client, &url, &format!("dependencies for {} {}", name, version))
+        .await
+        .map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("registry returned {} for {} {} dependencies", resp.status(), name, version));
+    }
+    let v: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("invalid JSON from registry for {} {} dependencies: {}", name, version, e))?;
+    Ok(v.get("dependencies")
+        .and_then(|d| d.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|d| {
+            let id = d.get("crate_id")?.as_str()?.to_string();
+            Some((id, d.get("optional").and_then(|o| o.as_bool()).unwrap_or(false)))
+        })
+        .collect())
+}
+
+/// The version's `features` table as returned by the registry.
+async fn fetch_features(client: &Client, registry_base: &str, name: &str, version: &str) -> Result<BTreeMap<String, Vec<String>>, String> {
+    let url = format!("{}/crates/{}/{}", registry_base, name, version);
+    let resp = registry_get(client, &url, &format!("{} {}", name, version))
+        .await
+        .map_err(|e| e.to_string())?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("{} {} does not exist on the registry", name, version));
+    }
+    if !resp.status().is_success() {
+        return Err(format!("registry returned {} for {} {}", resp.status(), name, version));
+    }
+    let v: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("invalid JSON from registry for {} {}: {}", name, version, e))?;
+    Ok(v.pointer("/version/features")
+        .and_then(|f| f.as_object())
+        .map(|features| {
+            features
+                .iter()
+                .map(|(k, v)| {
+                    let enabled = v
+                        .as_array()
+                        .map(|a| a.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect())
+                        .unwrap_or_default();
+                    (k.clone(), enabled)
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// Exact version when `wanted` is one, otherwise the newest non-yanked match (stable preferred).
+fn pick_version(versions: &[(Version, bool)], wanted: Option<&str>) -> Result<String, String> {
+    if let Some(exact) = wanted.and_then(|w| Version::parse(w.trim()).ok()) {
+        return versions
+            .iter()
+            .find(|(v, _)| *v == exact)
+            .map(|(v, _)| v.to_string())
+            .ok_or_else(|| format!("version {} is not published", exact));
+    }
+    let req = match wanted.map(str::trim).filter(|w| !w.is_empty()) {
+        Some(w) => VersionReq::parse(w).map_err(|e| format!("invalid version requirement '{}': {}", w, e))?,
+        None => VersionReq::STAR,
+    };
+    let live = versions.iter().filter(|(v, y)| !*y && req.matches(v)).map(|(v, _)| v);
+    live.clone()
+        .filter(|v| v.pre.is_empty())
+        .max()
+        .or_else(|| live.max())
+        .map(|v| v.to_string())
+        .ok_or_else(|| "no published, non-yanked version matches".to_string())
+}
+
+/// Split the raw feature table into nodes, adding Cargo's implicit feature for every
+/// optional dependency that is never referenced as `dep:name`.
+fn build_graph(features: &BTreeMap<String, Vec<String>>, optional_deps: &BTreeSet<String>) -> Vec<FeatureNode> {
+    let explicit_deps: HashSet<&str> = features
+        .values()
+        .flatten()
+        .filter_map(|e| e.strip_prefix("dep:"))
+        .collect();
+
+    let mut nodes: Vec<FeatureNode> = features
+        .iter()
+        .map(|(name, enabled)| {
+            let mut node = FeatureNode {
+                name: name.clone(),
+                enables_features: Vec::new(),
+                enables_dependencies: Vec::new(),
+                dependency_features: Vec::new(),
+                implicit: false,
+            };
+            for entry in enabled {
+                if let Some(dep) = entry.strip_prefix("dep:") {
+                    node.enables_dependencies.push(dep.to_string());
+                } else if let Some((dep, _)) = entry.split_once('/') {
+                    node.dependency_features.push(entry.clone());
+                    // `dep?/feat` only applies if something else enables `dep`
+                    if !dep.ends_with('?') && optional_deps.contains(dep) {
+                        node.enables_dependencies.push(dep.to_string());
+                        if !explicit_deps.contains(dep) {
+                            node.enables_features.push(dep.to_string());
+                        }
+                    }
+                } else {
+                    // another feature, or the implicit feature of an optional dependency
+                    node.enables_features.push(entry.clone());
+                }
+            }
+            node.enables_features.sort();
+            node.enables_features.dedup();
+            node.enables_dependencies.sort();
+            node.enables_dependencies.dedup();
+            node
+        })
+        .collect();
+
+    for dep in optional_deps {
+        if !explicit_deps.contains(dep.as_str()) && !features.contains_key(dep) {
+            nodes.push(FeatureNode {
+                name: dep.clone(),
+                enables_features: Vec::new(),
+                enables_dependencies: vec![dep.clone()],
+                dependency_features: Vec::new(),
+                implicit: true,
+            });
+        }
+    }
+    nodes.sort_by(|a, b| a.name.cmp(&b.name));
+    nodes
+}
+
+/// Indented tree from every root (`default` first, then features nothing else enables).
+/// A feature already expanded elsewhere is printed once more with "(see above)".
+fn render_tree(nodes: &[FeatureNode]) -> String {
+    let by_name: BTreeMap<&str, &FeatureNode> = nodes.iter().map(|n| (n.name.as_str(), n)).collect();
+    let enabled_by_something: HashSet<&str> = nodes
+        .iter()
+        .flat_map(|n| n.enables_features.iter().map(|s| s.as_str()))
+        .collect();
+    let mut roots: Vec<&str> = nodes
+        .iter()
+        .map(|n| n.name.as_str())
+        .filter(|n| *n != "default" && !enabled_by_something.contains(n))
+        .collect();
+    if by_name.contains_key("default") {
+        roots.insert(0, "default");
+    }
+
+    fn walk(
+        name: &str,
+        depth: usize,
+        by_name: &BTreeMap<&str, &FeatureNode>,
+        expanded: &mut HashSet<String>,
+        out: &mut String,
+    ) {
+        let indent = "  ".repeat(depth);
+        let Some(node) = by_name.get(name) else {
+            let _ = writeln!(out, "{}- {} (not a feature)", indent, name);
+            return;
+        };
+        if !expanded.insert(name.to_string()) {
+            let _ = writeln!(out, "{}- {} (see above)", indent, name);
+            return;
+        }
+        let _ = writeln!(out, "{}- {}{}", indent, name, if node.implicit { " (implicit)" } else { "" });
+        for f in &node.enables_features {
+            walk(f, depth + 1, by_name, expanded, out);
+        }
+        for d in &node.enables_dependencies {
+            let _ = writeln!(out, "{}  - dep:{}", indent, d);
+        }
+        for df in &node.dependency_features {
+            let _ = writeln!(out, "{}  - {}", indent, df);
+        }
+    }
+
+    let mut out = String::new();
+    let mut expanded = HashSet::new();
+    for root in roots {
+        walk(root, 0, &by_name, &mut expanded, &mut out);
+    }
+    // anything only reachable through a cycle
+    for node in nodes {
+        if !expanded.contains(&node.name) {
+            walk(&node.name, 0, &by_name, &mut expanded, &mut out);
+        }
+    }
+    out
+}
+
+#[tool(
+    name = "crate_feature_graph",
+    description = "Shows which features of a crate version enable which other features and optional dependencies, as an adjacency list plus a text tree. Use it to understand feature interdependencies before writing a manifest."
+)]
+pub async fn crate_feature_graph(
+    Parameters(args): Parameters<CrateFeatureGraphArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let name = args.crate_name.trim().to_string();
+    if name.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("crate_name is required.")]));
+    }
+    if let Err(e) = crate::config::config().check_crate_allowed(&name) {
+        return Ok(CallToolResult::error(vec![Content::text(e)]));
+    }
+    let registry_base = match parse_registry_base(&crate::config::config().registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let version = match fetch_versions(&client, &registry_base, &name)
+        .await
+        .and_then(|versions| pick_version(&versions, args.version.as_deref()))
+    {
+        Ok(v) => v,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+    let features = match fetch_features(&client, &registry_base, &name, &version).await {
+        Ok(f) => f,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+    let mut warnings = Vec::new();
+    let optional_dependencies: BTreeSet<String> = match fetch_dependencies(&client, &registry_base, &name, &version).await {
+        Ok(deps) => deps.into_iter().filter(|(_, optional)| *optional).map(|(d, _)| d).collect(),
+        Err(e) => {
+            // the feature table alone still gives a usable graph, minus optional dependencies
+            tracing::warn!(crate_name = %name, %version, "{}", e);
+            warnings.push(format!(
+                "could not fetch dependencies ({}); optional dependencies and implicit features are missing from the graph",
+                e
+            ));
+            BTreeSet::new()
+        }
+    };
+
+    let nodes = build_graph(&features, &optional_dependencies);
+    let response = FeatureGraphResponse {
+        crate_name: name,
+        version,
+        default_features: features.get("default").cloned().unwrap_or_default(),
+        optional_dependencies: optional_dependencies.into_iter().collect(),
+        tree: render_tree(&nodes),
+        features: nodes,
+        warnings,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
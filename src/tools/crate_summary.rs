@@ -0,0 +1,197 @@
+// src/tools/crate_summary.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::Deserialize;
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use std::future::Future;
+
+use crate::tools::query_rustdocs::{fetch_crates_io_readme, parse_registry_base, registry_get};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CrateSummaryArgs {
+    /// Crate to summarize.
+    #[schemars(example = "reqwest")]
+    crate_name: String,
+}
+
+/// README characters read when looking for the first paragraph.
+const README_SCAN_CHARS: usize = 8_000;
+
+/// Longest README excerpt quoted in the summary.
+const EXCERPT_MAX_CHARS: usize = 400;
+
+/// The first prose paragraph of a markdown README: headings, badges, images,
+/// HTML and code blocks before it are skipped.
+fn first_paragraph(markdown: &str) -> Option<String> {
+    let mut para: Vec<&str> = Vec::new();
+    let mut in_code = false;
+    for line in markdown.lines() {
+        let line = line.trim();
+        if line.starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        let skip = line.is_empty()
+            || line.starts_with('#')
+            || line.starts_with("![")
+            || line.starts_with("[![")
+            || line.starts_with('<')
+            || line.starts_with('|')
+            || line.starts_with("---");
+        if skip {
+            if !para.is_empty() {
+                break;
+            }
+            continue;
+        }
+        para.push(line);
+    }
+    let text = para.join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    Some(match text.char_indices().nth(EXCERPT_MAX_CHARS) {
+        Some((idx, _)) => format!("{}...", text[..idx].trim_end()),
+        None => text,
+    })
+}
+
+fn non_empty_str<'a>(v: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    v.get(key).and_then(|x| x.as_str()).map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// 1234567 -> "1.2M".
+fn human_count(n: u64) -> String {
+    match n {
+        0..=999 => n.to_string(),
+        1_000..=999_999 => format!("{:.1}K", n as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1}M", n as f64 / 1e6),
+        _ => format!("{:.1}B", n as f64 / 1e9),
+    }
+}
+
+/// "2024-05-01 (3 months ago)" from an RFC 3339 timestamp.
+fn describe_date(timestamp: &str, now: DateTime<Utc>) -> Option<String> {
+    let t = DateTime::parse_from_rfc3339(timestamp).ok()?.with_timezone(&Utc);
+    let days = (now - t).num_days().max(0);
+    let ago = match days {
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        2..=59 => format!("{} days ago", days),
+        60..=729 => format!("{} months ago", days / 30),
+        _ => format!("{} years ago", days / 365),
+    };
+    Some(format!("{} ({})", t.format("%Y-%m-%d"), ago))
+}
+
+#[tool(
+    name = "crate_summary",
+    description = "Summarizes a crate in a few sentences: what it does (description plus the README's first paragraph), latest version, downloads, license and when it was last updated. A fast \"should I use this crate\" overview; use query_rustdocs for actual API docs."
+)]
+pub async fn crate_summary(
+    Parameters(args): Parameters<CrateSummaryArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let name = args.crate_name.trim().to_string();
+    if name.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("crate_name is required.")]));
+    }
+    let cfg = crate::config::config();
+    if let Err(e) = cfg.check_crate_allowed(&name) {
+        return Ok(CallToolResult::error(vec![Content::text(e)]));
+    }
+    let registry_base = match parse_registry_base(&cfg.registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let url = format!("{}/crates/{}", registry_base, name);
+    let resp = match registry_get(&client, &url, &format!("crates.io metadata for '{}'", name)).await {
+        Ok(r) => r,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+    };
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(CallToolResult::error(vec![Content::text(format!("Crate '{}' not found on the registry.", name))]));
+    }
+    if !resp.status().is_success() {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "registry returned {} for '{}'",
+            resp.status(),
+            name
+        ))]));
+    }
+    let v: serde_json::Value = match resp.json().await {
+        Ok(v) => v,
+        Err(e) => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "invalid JSON from registry for '{}': {}",
+                name, e
+            ))]))
+        }
+    };
+
+    let krate = v.get("crate").cloned().unwrap_or_default();
+    let str_field = |key: &str| non_empty_str(&krate, key);
+    let display_name = str_field("name").unwrap_or(&name).to_string();
+    let Some(version) = str_field("max_stable_version").or_else(|| str_field("max_version")).map(str::to_string) else {
+        return Ok(CallToolResult::error(vec![Content::text(format!("'{}' has no published versions.", name))]));
+    };
+    let description = str_field("description").map(|d| d.split_whitespace().collect::<Vec<_>>().join(" "));
+    let license = v
+        .get("versions")
+        .and_then(|vs| vs.as_array())
+        .into_iter()
+        .flatten()
+        .find(|ver| ver.get("num").and_then(|n| n.as_str()) == Some(version.as_str()))
+        .and_then(|ver| ver.get("license"))
+        .and_then(|l| l.as_str())
+        .map(str::to_string);
+    let downloads = krate.get("downloads").and_then(|d| d.as_u64()).unwrap_or(0);
+    let recent_downloads = krate.get("recent_downloads").and_then(|d| d.as_u64());
+    let updated = str_field("updated_at").and_then(|t| describe_date(t, Utc::now()));
+    let repository = str_field("repository").map(str::to_string);
+
+    let excerpt = fetch_crates_io_readme(&client, &registry_base, &display_name, &version, README_SCAN_CHARS)
+        .await
+        .and_then(|readme| first_paragraph(&readme))
+        .filter(|p| description.as_deref() != Some(p.as_str()));
+
+    let mut text = format!("{} {}", display_name, version);
+    match description {
+        Some(ref d) => text.push_str(&format!(": {}", d.trim_end_matches('.'))),
+        None => text.push_str(" has no description on crates.io"),
+    }
+    text.push('.');
+    if let Some(p) = excerpt {
+        text.push_str(&format!(" From its README: \"{}\"", p));
+    }
+    text.push_str(&format!(" It has {} downloads in total", human_count(downloads)));
+    if let Some(r) = recent_downloads {
+        text.push_str(&format!(" ({} in the last 90 days)", human_count(r)));
+    }
+    text.push('.');
+    match license {
+        Some(l) => text.push_str(&format!(" License: {}.", l)),
+        None => text.push_str(" No license is declared."),
+    }
+    if let Some(u) = updated {
+        text.push_str(&format!(" Last updated {}.", u));
+    }
+    if let Some(r) = repository {
+        text.push_str(&format!(" Repository: {}", r));
+    }
+    text.push_str(&format!("\n\nAdd it with: {} = \"{}\"", display_name, version));
+
+    Ok(CallToolResult::success(vec![Content::text(text)]))
+}
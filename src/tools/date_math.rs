@@ -0,0 +1,188 @@
+// src/tools/date_math.rs
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone};
+use chrono_tz::Tz;
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use serde::Deserialize;
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+
+use std::fmt::Display;
+use std::future::Future;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DateMathArgs {
+    /// Starting point: "now" (default), RFC 3339, "YYYY-MM-DD HH:MM:SS" or "YYYY-MM-DD".
+    #[serde(default)]
+    #[schemars(description = "Base datetime or \"now\" (default)")]
+    base: Option<String>,
+
+    /// "add" or "subtract".
+    #[schemars(description = "Operation: add or subtract")]
+    operation: String,
+
+    /// Duration like "3d", "2h30m", "1w", "90s". Units: w, d, h, m, s.
+    #[schemars(description = "Duration spec, e.g. 3d, 2h30m, 1w (units: w, d, h, m, s)")]
+    duration: String,
+
+    /// IANA zone such as "Europe/Berlin". Uses the server's local zone when omitted.
+    #[serde(default)]
+    #[schemars(description = "Optional IANA timezone, e.g. America/New_York")]
+    timezone: Option<String>,
+}
+
+/// Calendar part (days) and clock part (seconds) of a duration spec.
+/// Days are applied on the wall clock so DST shifts don't move the time of day.
+#[derive(Debug, Default, Clone, Copy)]
+struct DurationSpec {
+    days: i64,
+    seconds: i64,
+}
+
+fn parse_duration_spec(spec: &str) -> Result<DurationSpec, String> {
+    let mut out = DurationSpec::default();
+    let mut num = String::new();
+    let mut any = false;
+    for ch in spec.trim().chars() {
+        if ch.is_ascii_digit() {
+            num.push(ch);
+            continue;
+        }
+        if ch.is_whitespace() {
+            continue;
+        }
+        let n: i64 = num
+            .parse()
+            .map_err(|_| format!("expected a number before '{}' in '{}'", ch, spec))?;
+        num.clear();
+        let (days, seconds) = match ch.to_ascii_lowercase() {
+            'w' => (n.checked_mul(7), Some(0)),
+            'd' => (Some(n), Some(0)),
+            'h' => (Some(0), n.checked_mul(3600)),
+            'm' => (Some(0), n.checked_mul(60)),
+            's' => (Some(0), Some(n)),
+            other => return Err(format!("unknown unit '{}' in '{}'; use w, d, h, m, s", other, spec)),
+        };
+        out.days = days.and_then(|d| out.days.checked_add(d)).ok_or("result is out of range")?;
+        out.seconds = seconds.and_then(|s| out.seconds.checked_add(s)).ok_or("result is out of range")?;
+        any = true;
+    }
+    if !num.is_empty() {
+        return Err(format!("number '{}' in '{}' is missing a unit", num, spec));
+    }
+    if !any {
+        return Err(format!("empty duration '{}'", spec));
+    }
+    Ok(out)
+}
+
+/// Parse the base datetime in `tz`; naive values are taken as wall-clock time in that zone.
+fn parse_base<Z: TimeZone>(s: Option<&str>, tz: &Z) -> Result<DateTime<Z>, String> {
+    let s = s.map(|s| s.trim()).unwrap_or("now");
+    if s.is_empty() || s.eq_ignore_ascii_case("now") {
+        return Ok(tz.from_utc_datetime(&chrono::Utc::now().naive_utc()));
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Ok(dt.with_timezone(tz));
+    }
+    for fmt in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+            return tz
+                .from_local_datetime(&naive)
+                .earliest()
+                .ok_or_else(|| format!("'{}' does not exist in that timezone", s));
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        let naive = date.and_hms_opt(0, 0, 0).ok_or("invalid date")?;
+        return tz
+            .from_local_datetime(&naive)
+            .earliest()
+            .ok_or_else(|| format!("'{}' does not exist in that timezone", s));
+    }
+    Err(format!(
+        "could not parse base '{}'. Use \"now\", RFC 3339, YYYY-MM-DD HH:MM:SS, or YYYY-MM-DD.",
+        s
+    ))
+}
+
+fn apply<Z: TimeZone>(base: DateTime<Z>, spec: DurationSpec, sign: i64) -> Result<DateTime<Z>, String> {
+    let tz = base.timezone();
+    let shifted_naive = Duration::try_days(spec.days * sign)
+        .and_then(|d| base.naive_local().checked_add_signed(d))
+        .ok_or("result is out of range")?;
+    // a wall-clock time skipped by DST resolves to the earliest valid instant
+    let shifted = tz
+        .from_local_datetime(&shifted_naive)
+        .earliest()
+        .or_else(|| {
+            let later = shifted_naive.checked_add_signed(Duration::hours(1))?;
+            tz.from_local_datetime(&later).earliest()
+        })
+        .ok_or("result falls in a timezone gap")?;
+    Duration::try_seconds(spec.seconds * sign)
+        .and_then(|d| shifted.checked_add_signed(d))
+        .ok_or_else(|| "result is out of range".to_string())
+}
+
+fn render<Z: TimeZone>(dt: &DateTime<Z>) -> String
+where
+    Z::Offset: Display,
+{
+    format!(
+        "ISO: {}\nHuman: {}",
+        dt.to_rfc3339(),
+        dt.format("%A, %B %-d, %Y at %I:%M:%S %p %Z")
+    )
+}
+
+#[tool(
+    name = "date_math",
+    description = "Adds or subtracts a duration (e.g. 3d, 2h30m, 1w) to a datetime or \"now\", optionally in an IANA timezone. Returns ISO and human-readable results."
+)]
+pub async fn date_math(
+    Parameters(args): Parameters<DateMathArgs>,
+) -> Result<CallToolResult, rmcp::ErrorData> {
+    let sign = match args.operation.trim().to_lowercase().as_str() {
+        "add" | "plus" | "+" => 1,
+        "subtract" | "sub" | "minus" | "-" => -1,
+        other => {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unsupported operation: '{}'. Use add or subtract.",
+                other
+            ))]));
+        }
+    };
+
+    let spec = match parse_duration_spec(&args.duration) {
+        Ok(s) => s,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let output = match args.timezone.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+        Some(name) => {
+            let tz: Tz = match name.parse() {
+                Ok(tz) => tz,
+                Err(_) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Unknown timezone: '{}'. Use an IANA name like Europe/Berlin.",
+                        name
+                    ))]));
+                }
+            };
+            parse_base(args.base.as_deref(), &tz)
+                .and_then(|base| apply(base, spec, sign))
+                .map(|dt| render(&dt))
+        }
+        None => parse_base(args.base.as_deref(), &Local)
+            .and_then(|base| apply(base, spec, sign))
+            .map(|dt| render(&dt)),
+    };
+
+    match output {
+        Ok(text) => Ok(CallToolResult::success(vec![Content::text(text)])),
+        Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+    }
+}
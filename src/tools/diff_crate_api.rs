@@ -0,0 +1,146 @@
+// src/tools/diff_crate_api.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::collections::BTreeMap;
+use std::future::Future;
+
+use crate::tools::api_surface::{load_surface, Surface, JSON_SOURCE};
+use crate::tools::query_rustdocs::parse_docs_base;
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiffCrateApiArgs {
+    /// Crate to compare.
+    #[schemars(example = "clap")]
+    crate_name: String,
+
+    /// The version being upgraded from, e.g. "3.2.25".
+    #[schemars(example = "3.2.25")]
+    from_version: String,
+
+    /// The version being upgraded to (default "latest").
+    #[serde(default)]
+    #[schemars(example = "4.5.4")]
+    to_version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SignatureChange {
+    path: String,
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, Serialize)]
+struct DiffCrateApiResponse {
+    crate_name: String,
+    from_version: String,
+    to_version: String,
+    /// Where the item lists came from; signatures are only compared with rustdoc JSON.
+    source: &'static str,
+    added: Vec<String>,
+    removed: Vec<String>,
+    /// Paths present in both versions, including those in `changed`.
+    retained: Vec<String>,
+    /// Retained items whose rendered signature differs (rustdoc JSON only).
+    changed: Vec<SignatureChange>,
+}
+
+/// Path -> rendered line; the first line wins when a path repeats.
+fn by_path(entries: Vec<(String, String)>) -> BTreeMap<String, String> {
+    let mut map = BTreeMap::new();
+    for (path, line) in entries {
+        map.entry(path).or_insert(line);
+    }
+    map
+}
+
+#[tool(
+    name = "diff_crate_api",
+    description = "Compares a crate's public API between two versions and returns the item paths that were added, removed and retained, plus retained items whose signature changed. Use it when upgrading a dependency to see exactly what changed."
+)]
+pub async fn diff_crate_api(
+    Parameters(args): Parameters<DiffCrateApiArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let crate_name = args.crate_name.trim().to_string();
+    let from = args.from_version.trim();
+    let to = args.to_version.as_deref().map(str::trim).filter(|v| !v.is_empty()).unwrap_or("latest");
+    if crate_name.is_empty() || from.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("crate_name and from_version are required.")]));
+    }
+    let cfg = crate::config::config();
+    if let Err(e) = cfg.check_crate_allowed(&crate_name) {
+        return Ok(CallToolResult::error(vec![Content::text(e)]));
+    }
+    let docs_base = match parse_docs_base(&cfg.docs_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let (old, new) = tokio::join!(
+        load_surface(&client, &docs_base, &crate_name, from, false),
+        load_surface(&client, &docs_base, &crate_name, to, false),
+    );
+    let (mut old, mut new) = match (old, new) {
+        (Ok(o), Ok(n)) => (o, n),
+        (Err(e), _) | (_, Err(e)) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+    // all.html lists no methods, so a JSON side would show every method as added or removed
+    if old.source != new.source {
+        let reload = |s: &Surface| if s.source == JSON_SOURCE { Some(s.version.clone()) } else { None };
+        if let Some(v) = reload(&old) {
+            old = match load_surface(&client, &docs_base, &crate_name, &v, true).await {
+                Ok(s) => s,
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            };
+        }
+        if let Some(v) = reload(&new) {
+            new = match load_surface(&client, &docs_base, &crate_name, &v, true).await {
+                Ok(s) => s,
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            };
+        }
+    }
+
+    let source = old.source;
+    let old_items = by_path(old.entries);
+    let new_items = by_path(new.entries);
+    let added: Vec<String> = new_items.keys().filter(|p| !old_items.contains_key(*p)).cloned().collect();
+    let removed: Vec<String> = old_items.keys().filter(|p| !new_items.contains_key(*p)).cloned().collect();
+    let retained: Vec<String> = old_items.keys().filter(|p| new_items.contains_key(*p)).cloned().collect();
+    let changed: Vec<SignatureChange> = if source == JSON_SOURCE {
+        retained
+            .iter()
+            .filter_map(|p| {
+                let (from, to) = (&old_items[p], &new_items[p]);
+                (from != to).then(|| SignatureChange { path: p.clone(), from: from.trim().to_string(), to: to.trim().to_string() })
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let response = DiffCrateApiResponse {
+        crate_name,
+        from_version: old.version,
+        to_version: new.version,
+        source,
+        added,
+        removed,
+        retained,
+        changed,
+    };
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
@@ -0,0 +1,286 @@
+// src/tools/docs_to_markdown.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::Deserialize;
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+
+use scraper::node::Node;
+use scraper::{ElementRef, Html};
+use std::future::Future;
+use std::time::Duration;
+
+use crate::tools::query_rustdocs::{
+    code_block_language, content_selectors, fetch_compressed_text, fetch_docs_page, item_path_candidates,
+    parse_docs_base,
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DocsToMarkdownArgs {
+    /// A docs.rs page URL. Takes precedence over `crate_name`/`item_path`.
+    #[serde(default)]
+    #[schemars(example = "https://docs.rs/tokio/latest/tokio/sync/struct.Mutex.html")]
+    url: Option<String>,
+
+    /// Crate whose docs to convert when no `url` is given.
+    #[serde(default)]
+    #[schemars(example = "tokio")]
+    crate_name: Option<String>,
+
+    /// Item inside the crate, e.g. "sync::Mutex", "spawn" or "sync" (a module). The crate root when omitted.
+    #[serde(default)]
+    #[schemars(example = "sync::Mutex")]
+    item_path: Option<String>,
+
+    /// docs.rs version segment (default "latest").
+    #[serde(default)]
+    version: Option<String>,
+}
+
+/// Item kinds tried, in order, when resolving `item_path` to a page.
+const ITEM_KINDS: &[&str] = &["struct", "enum", "trait", "fn", "macro", "type", "constant", "static", "union"];
+
+/// Elements that are page chrome rather than documentation.
+fn is_chrome(el: &ElementRef) -> bool {
+    let v = el.value();
+    matches!(v.name(), "script" | "style" | "nav" | "button" | "noscript" | "rustdoc-search" | "rustdoc-toolbar")
+        || v.classes().any(|c| matches!(c, "sidebar" | "anchor" | "src" | "out-of-band" | "tooltip" | "copy-path"))
+}
+
+/// Renders a docs page's main content region as markdown: headings, paragraphs,
+/// lists, links, emphasis, inline code and fenced code blocks.
+struct MarkdownWriter<'a> {
+    out: String,
+    page_url: &'a str,
+}
+
+impl MarkdownWriter<'_> {
+    fn block_break(&mut self) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if self.out.is_empty() || self.out.ends_with("\n\n") {
+            return;
+        }
+        self.out.push_str(if self.out.ends_with('\n') { "\n" } else { "\n\n" });
+    }
+
+    fn text(&mut self, text: &str) {
+        let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        if collapsed.is_empty() {
+            if text.chars().next().is_some_and(char::is_whitespace) && !self.out.ends_with([' ', '\n']) && !self.out.is_empty() {
+                self.out.push(' ');
+            }
+            return;
+        }
+        if text.starts_with(char::is_whitespace) && !self.out.ends_with([' ', '\n']) && !self.out.is_empty() {
+            self.out.push(' ');
+        }
+        self.out.push_str(&collapsed);
+        if text.ends_with(char::is_whitespace) {
+            self.out.push(' ');
+        }
+    }
+
+    fn children(&mut self, el: &ElementRef, list_depth: usize) {
+        for child in el.children() {
+            match child.value() {
+                Node::Text(t) => {
+                    let text: &str = t;
+                    self.text(text);
+                }
+                Node::Element(_) => {
+                    if let Some(child_el) = ElementRef::wrap(child) {
+                        self.element(&child_el, list_depth);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn element(&mut self, el: &ElementRef, list_depth: usize) {
+        if is_chrome(el) {
+            return;
+        }
+        let name = el.value().name();
+        match name {
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                let level = name[1..].parse::<usize>().unwrap_or(1);
+                self.block_break();
+                self.out.push_str(&"#".repeat(level));
+                self.out.push(' ');
+                let text = el.text().collect::<String>();
+                self.out.push_str(text.split_whitespace().collect::<Vec<_>>().join(" ").as_str());
+                self.block_break();
+            }
+            "pre" => {
+                let lang = code_block_language(el).unwrap_or_else(|| "rust".to_string());
+                let code = el.text().collect::<String>();
+                self.block_break();
+                self.out.push_str(&format!("```{}\n{}\n```", lang, code.trim_end()));
+                self.block_break();
+            }
+            "code" => {
+                let code = el.text().collect::<String>();
+                if !code.trim().is_empty() {
+                    self.out.push('`');
+                    self.out.push_str(code.trim());
+                    self.out.push('`');
+                }
+            }
+            "strong" | "b" => self.wrapped(el, "**", list_depth),
+            "em" | "i" => self.wrapped(el, "*", list_depth),
+            "a" => {
+                let href = el.value().attr("href").unwrap_or_default();
+                let target = reqwest::Url::parse(self.page_url)
+                    .ok()
+                    .and_then(|base| base.join(href).ok())
+                    .map(|u| u.to_string());
+                match target.filter(|_| !href.is_empty() && !href.starts_with('#')) {
+                    Some(url) => {
+                        let mut inner = MarkdownWriter { out: String::new(), page_url: self.page_url };
+                        inner.children(el, list_depth);
+                        let label = inner.out.trim().to_string();
+                        if !label.is_empty() {
+                            self.out.push_str(&format!("[{}]({})", label, url));
+                        }
+                    }
+                    None => self.children(el, list_depth),
+                }
+            }
+            "br" => self.out.push('\n'),
+            "ul" | "ol" => {
+                self.block_break();
+                let ordered = name == "ol";
+                let items = el.children().filter_map(ElementRef::wrap).filter(|c| c.value().name() == "li");
+                for (i, li) in items.enumerate() {
+                    if !self.out.is_empty() && !self.out.ends_with('\n') {
+                        self.out.push('\n');
+                    }
+                    self.out.push_str(&"  ".repeat(list_depth));
+                    if ordered {
+                        self.out.push_str(&format!("{}. ", i + 1));
+                    } else {
+                        self.out.push_str("- ");
+                    }
+                    let mut inner = MarkdownWriter { out: String::new(), page_url: self.page_url };
+                    inner.children(&li, list_depth + 1);
+                    self.out.push_str(inner.out.trim());
+                }
+                self.block_break();
+            }
+            "p" | "div" | "section" | "details" | "summary" | "table" | "tr" | "dl" | "dt" | "dd" | "blockquote" => {
+                self.block_break();
+                self.children(el, list_depth);
+                self.block_break();
+            }
+            _ => self.children(el, list_depth),
+        }
+    }
+
+    fn wrapped(&mut self, el: &ElementRef, marker: &str, list_depth: usize) {
+        let mut inner = MarkdownWriter { out: String::new(), page_url: self.page_url };
+        inner.children(el, list_depth);
+        let text = inner.out.trim();
+        if !text.is_empty() {
+            self.out.push_str(marker);
+            self.out.push_str(text);
+            self.out.push_str(marker);
+        }
+    }
+}
+
+/// Markdown for the main content region of a docs page (or the whole document
+/// when it has none, e.g. a rendered README).
+pub(crate) fn html_to_markdown(html: &str, page_url: &str) -> String {
+    let doc = Html::parse_document(html);
+    let root = content_selectors()
+        .iter()
+        .find_map(|sel| doc.select(sel).next())
+        .unwrap_or_else(|| {
+            tracing::debug!(page_url, "no content selector matched; converting the whole document");
+            doc.root_element()
+        });
+    let mut writer = MarkdownWriter { out: String::new(), page_url };
+    writer.element(&root, 0);
+    let mut out = String::new();
+    let mut blank_run = 0;
+    for line in writer.out.lines() {
+        let line = line.trim_end();
+        blank_run = if line.is_empty() { blank_run + 1 } else { 0 };
+        if blank_run <= 1 {
+            out.push_str(line);
+            out.push('\n');
+        }
+    }
+    out.trim().to_string()
+}
+
+#[tool(
+    name = "docs_to_markdown",
+    description = "Fetches a docs.rs page (by URL, or crate plus optional item path like \"sync::Mutex\") and returns its main content as markdown with headings, lists, links and ```rust code fences."
+)]
+pub async fn docs_to_markdown(
+    Parameters(args): Parameters<DocsToMarkdownArgs>,
+) -> Result<CallToolResult, ErrorData> {
+    let delay = Duration::from_millis(crate::config::config().crawl_delay_ms);
+    let docs_base = match parse_docs_base(&crate::config::config().docs_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let page = if let Some(url) = args.url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        // only the configured docs host (docs.rs by default) is fetched
+        let Some(rest) = url.strip_prefix(docs_base.as_str()).filter(|r| r.starts_with('/')) else {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Only pages under {} are supported.",
+                docs_base
+            ))]));
+        };
+        if let Some(name) = rest.split('/').nth(1).filter(|n| !n.is_empty() && *n != "crate") {
+            if let Err(e) = crate::config::config().check_crate_allowed(name) {
+                return Ok(CallToolResult::error(vec![Content::text(e)]));
+            }
+        }
+        fetch_compressed_text(url, delay).await
+    } else if let Some(crate_name) = args.crate_name.as_deref().map(str::trim).filter(|c| !c.is_empty()) {
+        if let Err(e) = crate::config::config().check_crate_allowed(crate_name) {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+        let version = args.version.as_deref().map(str::trim).filter(|v| !v.is_empty()).unwrap_or("latest");
+        let lib = crate_name.replace('-', "_");
+        let candidates = match args.item_path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+            Some(item) => {
+                let mut c = item_path_candidates(crate_name, item, ITEM_KINDS);
+                // a module path: "sync" or "tokio::sync"
+                let module = item.trim_start_matches(&format!("{}::", lib)).replace("::", "/");
+                c.push(format!("{}/{}/index.html", lib, module));
+                c
+            }
+            None => vec![format!("{}/index.html", lib)],
+        };
+        let mut found = None;
+        for cand in candidates {
+            if let Some(page) = fetch_docs_page(&docs_base, crate_name, version, &cand, delay).await {
+                found = Some(page);
+                break;
+            }
+        }
+        found
+    } else {
+        return Ok(CallToolResult::error(vec![Content::text("Pass either `url` or `crate_name`.")]));
+    };
+
+    let Some((final_url, html)) = page else {
+        return Ok(CallToolResult::error(vec![Content::text("Could not fetch the docs.rs page.")]));
+    };
+    let markdown = html_to_markdown(&html, &final_url);
+    if markdown.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text(format!("No content found on {}.", final_url))]));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(format!("<!-- source: {} -->\n\n{}", final_url, markdown))]))
+}
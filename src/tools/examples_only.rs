@@ -0,0 +1,178 @@
+// src/tools/examples_only.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::future::Future;
+
+use crate::tools::query_rustdocs::{
+    discover_github_default_branch, fetch_github_examples, find_release_tag, locate_package_subpath,
+    normalize_example_filter, parse_github_owner_repo, parse_registry_base, registry_get, resolve_version_req,
+    ExampleFile, GithubPackage,
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExamplesOnlyArgs {
+    /// Crate whose examples to fetch.
+    #[schemars(example = "axum")]
+    crate_name: String,
+
+    /// Version requirement to match, e.g. "0.7"; the latest stable release when omitted.
+    #[serde(default)]
+    version: Option<String>,
+
+    /// Maximum example files to fetch (default 20).
+    #[serde(default)]
+    examples_max_files: Option<usize>,
+
+    /// Also fetch integration tests from `tests/`, sharing the `examples_max_files` cap.
+    #[serde(default)]
+    include_tests: Option<bool>,
+
+    /// Only fetch files whose path contains this text (case-insensitive), e.g. "server".
+    #[serde(default)]
+    example_filter: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExamplesOnlyResponse {
+    crate_name: String,
+    version: String,
+    repository: String,
+    /// Release tag the files were read from, or the default branch when no tag matched.
+    git_ref: String,
+    ref_is_release_tag: bool,
+    repository_subpath: Option<String>,
+    examples: Vec<ExampleFile>,
+    tests: Vec<ExampleFile>,
+    errors: Vec<String>,
+}
+
+/// (latest stable version, repository URL) from the registry's crate endpoint.
+pub(crate) async fn fetch_crate_summary(client: &Client, registry_base: &str, name: &str) -> Result<(String, Option<String>), String> {
+    let url = format!("{}/crates/{}", registry_base, name);
+    let resp = registry_get(client, &url, &format!("crates.io metadata for '{}'", name))
+        .await
+        .map_err(|e| e.to_string())?;
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(format!("crate '{}' does not exist on the registry", name));
+    }
+    if !resp.status().is_success() {
+        return Err(format!("registry returned {} for '{}'", resp.status(), name));
+    }
+    let v: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("invalid JSON from registry for '{}': {}", name, e))?;
+    let krate = v.get("crate").ok_or_else(|| format!("unexpected registry shape for '{}'", name))?;
+    let version = ["max_stable_version", "max_version", "newest_version"]
+        .iter()
+        .find_map(|k| krate.get(*k).and_then(|x| x.as_str()))
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("could not determine the latest version of '{}'", name))?;
+    let repository = krate.get("repository").and_then(|r| r.as_str()).map(|s| s.to_string());
+    Ok((version, repository))
+}
+
+#[tool(
+    name = "examples_only",
+    description = "Fast path to runnable example code: resolves a crate's version and GitHub repository, then fetches files from its examples/ directory (at the matching release tag when there is one) without crawling docs."
+)]
+pub async fn examples_only(
+    Parameters(args): Parameters<ExamplesOnlyArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let name = args.crate_name.trim().to_string();
+    if name.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("crate_name is required.")]));
+    }
+    let cfg = crate::config::config();
+    if let Err(e) = cfg.check_crate_allowed(&name) {
+        return Ok(CallToolResult::error(vec![Content::text(e)]));
+    }
+    let registry_base = match parse_registry_base(&cfg.registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let (latest, repository) = match fetch_crate_summary(&client, &registry_base, &name).await {
+        Ok(s) => s,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+    let version = match args.version.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(req) => match resolve_version_req(&client, &registry_base, &name, req).await {
+            Ok(v) => v,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        },
+        None => latest,
+    };
+    let Some(repository) = repository else {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "{} {} lists no repository, so there are no examples to fetch.",
+            name, version
+        ))]));
+    };
+    let Some((owner, repo)) = parse_github_owner_repo(&repository) else {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "{}'s repository ({}) is not on GitHub; only GitHub examples are supported.",
+            name, repository
+        ))]));
+    };
+
+    let mut errors = Vec::new();
+    let branch = discover_github_default_branch(&client, &owner, &repo).await.unwrap_or_else(|| "main".to_string());
+    let repository_subpath = locate_package_subpath(&client, &owner, &repo, &branch, &name).await;
+    if repository_subpath.is_none() {
+        errors.push(format!(
+            "Could not find a Cargo.toml for package '{}' in {}/{}; examples are from the repo root and may belong to another package",
+            name, owner, repo
+        ));
+    }
+    let subpath = repository_subpath.clone().unwrap_or_default();
+    let tag = find_release_tag(&client, &owner, &repo, &name, &version, &subpath).await;
+    if tag.is_none() {
+        errors.push(format!("No release tag found for {} {}; examples are from branch '{}' and may be newer", name, version, branch));
+    }
+    let git_ref = tag.clone().unwrap_or(branch);
+
+    let filter = normalize_example_filter(args.example_filter.as_deref());
+    let (examples, tests) = fetch_github_examples(
+        &client,
+        &GithubPackage { owner: &owner, repo: &repo, git_ref: &git_ref, subpath: &subpath },
+        args.examples_max_files.unwrap_or(20),
+        args.include_tests.unwrap_or(false),
+        filter.as_deref(),
+        true,
+    )
+    .await;
+    if examples.is_empty() {
+        errors.push(match filter {
+            Some(f) => format!("No example files matching '{}' found in {}/{} at '{}'", f, owner, repo, git_ref),
+            None => format!("No example files found in {}/{} at '{}'", owner, repo, git_ref),
+        });
+    }
+
+    let response = ExamplesOnlyResponse {
+        crate_name: name,
+        version,
+        repository,
+        ref_is_release_tag: tag.is_some(),
+        git_ref,
+        repository_subpath,
+        examples,
+        tests,
+        errors,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
@@ -0,0 +1,139 @@
+// src/tools/expand_crate_prefix.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::future::Future;
+
+use crate::tools::query_rustdocs::{parse_registry_base, registry_get};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExpandCratePrefixArgs {
+    /// Name prefix, e.g. "tokio-" or "serde_". `-` and `_` match each other.
+    #[schemars(example = "tokio-")]
+    prefix: String,
+
+    /// Most crates returned (default 25, max 100).
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct PrefixMatch {
+    name: String,
+    description: Option<String>,
+    downloads: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct ExpandCratePrefixResponse {
+    prefix: String,
+    /// Matching crates, most downloaded first.
+    crates: Vec<PrefixMatch>,
+    /// Every match the search returned is listed; false when `limit` or the page cap cut it short.
+    complete: bool,
+}
+
+/// Search pages read looking for prefix matches.
+const MAX_SEARCH_PAGES: usize = 5;
+
+/// crates.io treats `-` and `_` in names as the same.
+fn normalize(name: &str) -> String {
+    name.to_lowercase().replace('_', "-")
+}
+
+#[tool(
+    name = "expand_crate_prefix",
+    description = "Lists published crates whose names start with a prefix (e.g. \"tokio-\" -> tokio-util, tokio-stream, ...), most downloaded first, with description and downloads. Use it to discover the ecosystem around a base crate."
+)]
+pub async fn expand_crate_prefix(
+    Parameters(args): Parameters<ExpandCratePrefixArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let prefix = args.prefix.trim().trim_end_matches('*').to_string();
+    if prefix.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("prefix is required.")]));
+    }
+    let limit = args.limit.unwrap_or(25).clamp(1, 100);
+    let cfg = crate::config::config();
+    let registry_base = match parse_registry_base(&cfg.registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    // the search matches words anywhere, so it is sorted by downloads and filtered by name here
+    let wanted = normalize(&prefix);
+    let query = prefix.trim_end_matches(['-', '_']);
+    let mut crates = Vec::new();
+    let mut complete = true;
+    for page in 1..=MAX_SEARCH_PAGES {
+        let what = format!("crates.io search for '{}'", prefix);
+        let page_param = page.to_string();
+        let url = match reqwest::Url::parse_with_params(
+            &format!("{}/crates", registry_base),
+            [("q", query), ("sort", "downloads"), ("per_page", "100"), ("page", page_param.as_str())],
+        ) {
+            Ok(u) => u,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(format!("invalid search URL: {}", e))])),
+        };
+        let resp = match registry_get(&client, url.as_str(), &what).await {
+            Ok(r) => r,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+        if !resp.status().is_success() {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "registry returned {} for the {}",
+                resp.status(),
+                what
+            ))]));
+        }
+        let v: serde_json::Value = match resp.json().await {
+            Ok(v) => v,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "invalid JSON from the {}: {}",
+                    what, e
+                ))]))
+            }
+        };
+        let hits = v.get("crates").and_then(|c| c.as_array()).cloned().unwrap_or_default();
+        for c in &hits {
+            let Some(name) = c.get("name").and_then(|n| n.as_str()) else { continue };
+            if !normalize(name).starts_with(&wanted) {
+                continue;
+            }
+            crates.push(PrefixMatch {
+                name: name.to_string(),
+                description: c
+                    .get("description")
+                    .and_then(|d| d.as_str())
+                    .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" ")),
+                downloads: c.get("downloads").and_then(|d| d.as_u64()).unwrap_or(0),
+            });
+        }
+        if crates.len() >= limit {
+            crates.truncate(limit);
+            complete = false;
+            break;
+        }
+        if hits.len() < 100 {
+            break;
+        }
+        if page == MAX_SEARCH_PAGES {
+            complete = false;
+        }
+    }
+
+    let response = ExpandCratePrefixResponse { prefix, crates, complete };
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
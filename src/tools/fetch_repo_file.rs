@@ -0,0 +1,143 @@
+// src/tools/fetch_repo_file.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::future::Future;
+
+use crate::tools::examples_only::fetch_crate_summary;
+use crate::tools::query_rustdocs::{
+    discover_github_default_branch, fetch_github_raw_file, find_release_tag, locate_package_subpath,
+    parse_github_owner_repo, parse_registry_base, repo_path, resolve_version_req, ExampleFile,
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FetchRepoFileArgs {
+    /// Crate whose repository to read.
+    #[schemars(example = "tokio")]
+    crate_name: String,
+
+    /// File path relative to the crate's package directory (falling back to the repository
+    /// root), e.g. "src/sync/mutex.rs" or "examples/echo.rs".
+    #[schemars(example = "src/sync/mutex.rs")]
+    path: String,
+
+    /// Version requirement to match, e.g. "1.35"; the latest stable release when omitted.
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FetchRepoFileResponse {
+    crate_name: String,
+    version: String,
+    repository: String,
+    /// Release tag the file was read from, or the default branch when no tag matched.
+    git_ref: String,
+    ref_is_release_tag: bool,
+    /// `path` is relative to the repository root here.
+    file: ExampleFile,
+    errors: Vec<String>,
+}
+
+#[tool(
+    name = "fetch_repo_file",
+    description = "Fetches one file (e.g. \"src/sync/mutex.rs\" or \"examples/echo.rs\") from a crate's GitHub repository at the release tag matching its version, size-capped. Use it to read a specific source module or shipped example."
+)]
+pub async fn fetch_repo_file(
+    Parameters(args): Parameters<FetchRepoFileArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let name = args.crate_name.trim().to_string();
+    let path = args.path.trim().trim_start_matches("./").trim_start_matches('/').to_string();
+    if name.is_empty() || path.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("crate_name and path are required.")]));
+    }
+    // segments end up in raw.githubusercontent.com and GitHub API URLs: refuse anything that
+    // could step out of the repository (`..`, `%2e%2e`) or change the query (`?`, `#`)
+    if path.split('/').any(|seg| matches!(seg, "" | "." | "..") || seg.contains(['%', '?', '#', '\\'])) {
+        return Ok(CallToolResult::error(vec![Content::text(
+            "path must stay inside the repository: no empty, '.' or '..' segments and no %, ?, # or \\.",
+        )]));
+    }
+    let cfg = crate::config::config();
+    if let Err(e) = cfg.check_crate_allowed(&name) {
+        return Ok(CallToolResult::error(vec![Content::text(e)]));
+    }
+    let registry_base = match parse_registry_base(&cfg.registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let (latest, repository) = match fetch_crate_summary(&client, &registry_base, &name).await {
+        Ok(s) => s,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+    let version = match args.version.as_deref().map(str::trim).filter(|v| !v.is_empty()) {
+        Some(req) => match resolve_version_req(&client, &registry_base, &name, req).await {
+            Ok(v) => v,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        },
+        None => latest,
+    };
+    let Some(repository) = repository else {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "{} {} lists no repository.",
+            name, version
+        ))]));
+    };
+    let Some((owner, repo)) = parse_github_owner_repo(&repository) else {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "{}'s repository ({}) is not on GitHub; only GitHub repositories are supported.",
+            name, repository
+        ))]));
+    };
+
+    let mut errors = Vec::new();
+    let branch = discover_github_default_branch(&client, &owner, &repo).await.unwrap_or_else(|| "main".to_string());
+    let subpath = locate_package_subpath(&client, &owner, &repo, &branch, &name).await.unwrap_or_default();
+    let tag = find_release_tag(&client, &owner, &repo, &name, &version, &subpath).await;
+    if tag.is_none() {
+        errors.push(format!("No release tag found for {} {}; the file is from branch '{}' and may be newer", name, version, branch));
+    }
+    let git_ref = tag.clone().unwrap_or(branch);
+
+    let mut candidates = vec![repo_path(&subpath, &path)];
+    if !subpath.is_empty() {
+        candidates.push(path.clone());
+    }
+    let mut file = None;
+    for cand in candidates {
+        if let Some(content) = fetch_github_raw_file(&client, &owner, &repo, &git_ref, &cand).await {
+            file = Some(ExampleFile::new(cand, content));
+            break;
+        }
+    }
+    let Some(file) = file else {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "'{}' not found in {}/{} at '{}'.",
+            path, owner, repo, git_ref
+        ))]));
+    };
+
+    let response = FetchRepoFileResponse {
+        crate_name: name,
+        version,
+        repository,
+        ref_is_release_tag: tag.is_some(),
+        git_ref,
+        file,
+        errors,
+    };
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
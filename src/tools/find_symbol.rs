@@ -0,0 +1,153 @@
+// src/tools/find_symbol.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::tools::query_rustdocs::{
+    all_items_path, docs_base_from_url, extract_all_items_from_html, fetch_docs_page, parse_docs_base,
+};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindSymbolArgs {
+    /// Item name, optionally with part of its module path.
+    #[schemars(description = "Symbol to look for, e.g. HashMap, spawn or sync::Mutex", example = "Mutex")]
+    symbol: String,
+
+    /// Crates to search, each at its latest docs.rs build.
+    #[schemars(description = "Candidate crates to search", example = ["tokio", "parking_lot"])]
+    crates: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct SymbolMatch {
+    /// Full path, e.g. "tokio::sync::Mutex".
+    path: String,
+    kind: String,
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+struct CrateSymbolResult {
+    crate_name: String,
+    version: Option<String>,
+    found: bool,
+    /// Exact-case matches first.
+    matches: Vec<SymbolMatch>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FindSymbolResponse {
+    symbol: String,
+    /// Crates with at least one match, in the order given.
+    found_in: Vec<String>,
+    results: Vec<CrateSymbolResult>,
+}
+
+/// Look `symbol` up in `crate_name`'s `all.html` item list.
+async fn search_crate(docs_base: String, crate_name: String, symbol: String, delay: Duration) -> CrateSymbolResult {
+    let mut result = CrateSymbolResult {
+        crate_name: crate_name.clone(),
+        version: None,
+        found: false,
+        matches: Vec::new(),
+        error: None,
+    };
+    if let Err(e) = crate::config::config().check_crate_allowed(&crate_name) {
+        result.error = Some(e);
+        return result;
+    }
+    let lib = crate_name.replace('-', "_");
+    let Some((final_url, html)) = fetch_docs_page(&docs_base, &crate_name, "latest", &format!("{}/all.html", lib), delay).await else {
+        result.error = Some(format!("could not fetch the docs.rs item list for '{}'", crate_name));
+        return result;
+    };
+    let base = docs_base_from_url(&final_url);
+    result.version = base
+        .as_deref()
+        .and_then(|b| b.trim_end_matches('/').rsplit('/').next())
+        .map(|v| v.to_string());
+
+    let needle = symbol.trim_start_matches("::");
+    let mut matches: Vec<(bool, SymbolMatch)> = extract_all_items_from_html(&html)
+        .into_iter()
+        .filter_map(|item| {
+            let path = all_items_path(&lib, &item);
+            let lower = path.to_lowercase();
+            let wanted = needle.to_lowercase();
+            if lower != wanted && !lower.ends_with(&format!("::{}", wanted)) {
+                return None;
+            }
+            let exact = path == needle || path.ends_with(&format!("::{}", needle));
+            let url = match base {
+                Some(ref b) => format!("{}{}/{}", b, lib, item.path),
+                None => item.path.clone(),
+            };
+            Some((exact, SymbolMatch { path, kind: item.kind, url }))
+        })
+        .collect();
+    matches.sort_by_key(|(exact, m)| (!*exact, m.path.len()));
+    result.matches = matches.into_iter().map(|(_, m)| m).collect();
+    result.found = !result.matches.is_empty();
+    result
+}
+
+#[tool(
+    name = "find_symbol",
+    description = "Checks which of several crates provides an item (type, function, trait, macro, ...) by name, returning each match's full path, kind and docs.rs link. Use it to find the right crate for a type instead of guessing."
+)]
+pub async fn find_symbol(
+    Parameters(args): Parameters<FindSymbolArgs>,
+) -> Result<CallToolResult, ErrorData> {
+    let symbol = args.symbol.trim().to_string();
+    if symbol.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("symbol is required.")]));
+    }
+    let cfg = crate::config::config();
+    let crates: Vec<String> = args
+        .crates
+        .iter()
+        .map(|c| c.trim().to_string())
+        .filter(|c| !c.is_empty())
+        .take(cfg.max_crates)
+        .collect();
+    if crates.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("Pass at least one crate in `crates`.")]));
+    }
+
+    let docs_base = match parse_docs_base(&cfg.docs_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+    let delay = Duration::from_millis(cfg.crawl_delay_ms);
+    let handles: Vec<_> = crates
+        .into_iter()
+        .map(|c| tokio::spawn(search_crate(docs_base.clone(), c, symbol.clone(), delay)))
+        .collect();
+    let mut results = Vec::new();
+    for h in handles {
+        if let Ok(r) = h.await {
+            results.push(r);
+        }
+    }
+
+    let response = FindSymbolResponse {
+        found_in: results.iter().filter(|r| r.found).map(|r| r.crate_name.clone()).collect(),
+        symbol,
+        results,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
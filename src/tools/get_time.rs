@@ -1,6 +1,7 @@
 // src/tools/get_time.rs
 
-use chrono::{Local, Datelike, Timelike};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Utc, Datelike, Timelike};
+use chrono_tz::Tz;
 use rmcp::tool;
 use rmcp::handler::server::tool::Parameters;
 use rmcp::model::{CallToolResult, Content};
@@ -10,24 +11,84 @@ use rmcp::schemars;
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetTimeArgs {
-    /// Optional format: "12hr", "24hr", "iso", or "unix"
+    /// Optional format: "12hr", "24hr", "iso", "unix", "relative", "rfc2822", or "week"
     #[serde(default)]
-    #[schemars(description = "Optional format style: 12hr, 24hr, iso, or unix")]
+    #[schemars(description = "Optional format style: 12hr, 24hr, iso, unix, relative, rfc2822, or week", example = "iso")]
     format: Option<String>,
+
+    /// Reference datetime for the "relative" format (RFC 3339, "YYYY-MM-DD HH:MM:SS" or "YYYY-MM-DD", in the server's zone).
+    #[serde(default)]
+    #[schemars(description = "Datetime to describe relative to now, used with format=relative", example = "2025-01-31 09:00:00")]
+    reference: Option<String>,
+
+    /// Report in UTC regardless of the server's zone; combines with any `format`.
+    #[serde(default)]
+    #[schemars(description = "Render the time in UTC instead of the server's zone", example = true)]
+    utc: Option<bool>,
+}
+
+/// The zone times are reported in: `MCP_DEFAULT_TZ` when set, otherwise the system's local zone.
+fn default_tz() -> Option<Tz> {
+    crate::config::config().default_tz.as_deref().and_then(|name| name.parse().ok())
+}
+
+/// Parse a user-supplied datetime; naive values are taken in `tz` (local time when `None`).
+fn parse_reference(s: &str, tz: Option<Tz>) -> Option<DateTime<FixedOffset>> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt);
+    }
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .or_else(|| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok()?.and_hms_opt(0, 0, 0))?;
+    match tz {
+        Some(tz) => tz.from_local_datetime(&naive).earliest().map(|d| d.fixed_offset()),
+        None => Local.from_local_datetime(&naive).earliest().map(|d| d.fixed_offset()),
+    }
+}
+
+/// "3 hours", "1 day", "45 seconds" — largest whole unit only.
+fn humanize_duration(secs: i64) -> String {
+    let secs = secs.unsigned_abs();
+    let units = [
+        (365 * 24 * 3600, "year"),
+        (30 * 24 * 3600, "month"),
+        (7 * 24 * 3600, "week"),
+        (24 * 3600, "day"),
+        (3600, "hour"),
+        (60, "minute"),
+        (1, "second"),
+    ];
+    for (size, name) in units {
+        if secs >= size {
+            let n = secs / size;
+            return format!("{} {}{}", n, name, if n == 1 { "" } else { "s" });
+        }
+    }
+    "0 seconds".to_string()
 }
 
 #[tool(
     name = "get_time",
-    description = "Returns the current time in the server's local timezone. Defaults to a readable 12-hour AM/PM format."
+    description = "Returns the current time in the server's timezone (MCP_DEFAULT_TZ, or the system's local zone). Defaults to a readable 12-hour AM/PM format."
 )]
 pub async fn get_time(
     Parameters(args): Parameters<GetTimeArgs>,
 ) -> Result<CallToolResult, rmcp::ErrorData> {
-    // Get current time in system's local timezone
-    let now = Local::now();
-    
-    // Get timezone name (e.g., "PST", "EST", "CET") using format specifier
-    let tz_str = now.format("%Z").to_string();
+    // Get current time in the configured zone, else the system's local timezone,
+    // along with its name (e.g., "PST", "EST", "CET") from the format specifier
+    let tz = if args.utc.unwrap_or(false) { Some(Tz::UTC) } else { default_tz() };
+    let (now, tz_str) = match tz {
+        Some(tz) => {
+            let now = Utc::now().with_timezone(&tz);
+            (now.fixed_offset(), now.format("%Z").to_string())
+        }
+        None => {
+            let now = Local::now();
+            (now.fixed_offset(), now.format("%Z").to_string())
+        }
+    };
     let tz_str = if tz_str.is_empty() { "Local Time".to_string() } else { tz_str };
 
     // Match format
@@ -63,8 +124,44 @@ pub async fn get_time(
 
         "unix" => now.timestamp().to_string(),
 
+        "rfc2822" => now.to_rfc2822(),
+
+        "week" => {
+            let week = now.iso_week();
+            format!(
+                "{}-W{:02} (weekday {}, {})",
+                week.year(),
+                week.week(),
+                now.weekday().number_from_monday(),
+                now.format("%A")
+            )
+        }
+
+        "relative" => match args.reference.as_deref() {
+            Some(r) => match parse_reference(r, tz) {
+                Some(reference) => {
+                    let diff = (now - reference).num_seconds();
+                    if diff.abs() < 1 {
+                        "just now".to_string()
+                    } else if diff > 0 {
+                        format!("{} ago", humanize_duration(diff))
+                    } else {
+                        format!("in {}", humanize_duration(diff))
+                    }
+                }
+                None => format!(
+                    "Could not parse reference '{}'. Use RFC 3339, YYYY-MM-DD HH:MM:SS, or YYYY-MM-DD.",
+                    r
+                ),
+            },
+            None => {
+                let since_midnight = now.num_seconds_from_midnight() as i64;
+                format!("{} into the day ({})", humanize_duration(since_midnight), tz_str)
+            }
+        },
+
         invalid => format!(
-            "Unsupported format: '{}'. Try 12hr, 24hr, iso, or unix.",
+            "Unsupported format: '{}'. Try 12hr, 24hr, iso, unix, relative, rfc2822, or week.",
             invalid
         ),
     };
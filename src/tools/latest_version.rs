@@ -0,0 +1,111 @@
+// src/tools/latest_version.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::future::Future;
+
+use crate::tools::query_rustdocs::{fetch_crates_io_best_version, parse_registry_base};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LatestVersionArgs {
+    /// Crate names to look up.
+    #[schemars(description = "Crate names", example = ["serde", "tokio"])]
+    crates: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LatestVersionResult {
+    name: String,
+    latest_version: Option<String>,
+    dependency_line: Option<String>,
+    /// Why this version was picked, e.g. "highest stable non-yanked version".
+    selection_reason: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LatestVersionResponse {
+    /// Ready-to-paste `[dependencies]` section for every crate that resolved.
+    dependencies_block: String,
+    results: Vec<LatestVersionResult>,
+}
+
+async fn lookup(client: Client, registry_base: String, name: String) -> LatestVersionResult {
+    let mut result = LatestVersionResult {
+        name: name.clone(),
+        latest_version: None,
+        dependency_line: None,
+        selection_reason: None,
+        error: None,
+    };
+    if let Err(e) = crate::config::config().check_crate_allowed(&name) {
+        result.error = Some(e);
+        return result;
+    }
+    match fetch_crates_io_best_version(&client, &registry_base, &name, false).await {
+        Ok(meta) => {
+            result.dependency_line = Some(format!(r#"{} = "{}""#, name, meta.latest_version));
+            result.latest_version = Some(meta.latest_version);
+            result.selection_reason = Some(meta.selection_reason);
+        }
+        Err(e) => result.error = Some(e.to_string()),
+    }
+    result
+}
+
+#[tool(
+    name = "latest_version",
+    description = "Returns just the latest version and a Cargo dependency line for one or more crates, from the registry only (no docs.rs or GitHub requests). Use it when you only need current versions to write a manifest."
+)]
+pub async fn latest_version(
+    Parameters(args): Parameters<LatestVersionArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let cfg = crate::config::config();
+    let mut names: Vec<String> = Vec::new();
+    for c in &args.crates {
+        let c = c.trim();
+        if !c.is_empty() && !names.iter().any(|n| n == c) {
+            names.push(c.to_string());
+        }
+    }
+    names.truncate(cfg.max_crates);
+    if names.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("Pass at least one crate in `crates`.")]));
+    }
+    let registry_base = match parse_registry_base(&cfg.registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let handles: Vec<_> = names
+        .into_iter()
+        .map(|n| tokio::spawn(lookup(client.clone(), registry_base.clone(), n)))
+        .collect();
+    let mut results = Vec::new();
+    for h in handles {
+        if let Ok(r) = h.await {
+            results.push(r);
+        }
+    }
+
+    let mut dependencies_block = String::from("[dependencies]\n");
+    for line in results.iter().filter_map(|r| r.dependency_line.as_deref()) {
+        dependencies_block.push_str(line);
+        dependencies_block.push('\n');
+    }
+    let response = LatestVersionResponse { dependencies_block, results };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
@@ -0,0 +1,55 @@
+// src/tools/metrics.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use std::future::Future;
+use std::sync::Arc;
+
+use crate::metrics::{Metrics, ToolMetricsSnapshot};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MetricsArgs {
+    /// Only report this tool. Reports every tool when omitted.
+    #[serde(default)]
+    #[schemars(description = "Optional tool name to filter on")]
+    tool: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MetricsResponse {
+    total_invocations: u64,
+    total_errors: u64,
+    tools: Vec<ToolMetricsSnapshot>,
+}
+
+#[tool(
+    name = "metrics",
+    description = "Returns per-tool invocation count, error count and latency since the server started."
+)]
+pub async fn metrics(
+    Parameters(args): Parameters<MetricsArgs>,
+    metrics: Arc<Metrics>,
+) -> Result<CallToolResult, ErrorData> {
+    let mut tools = metrics.snapshot();
+    if let Some(ref name) = args.tool {
+        tools.retain(|t| &t.tool == name);
+    }
+
+    let response = MetricsResponse {
+        total_invocations: tools.iter().map(|t| t.invocations).sum(),
+        total_errors: tools.iter().map(|t| t.errors).sum(),
+        tools,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
@@ -1,3 +1,24 @@
 pub mod get_time;
 pub mod boilerplate_example;
 pub mod query_rustdocs;
+pub mod ping;
+pub mod metrics;
+pub mod date_math;
+pub mod recommend_crate;
+pub mod cache_admin;
+pub mod check_resolvable;
+pub mod convert_units;
+pub mod version;
+pub mod crate_feature_graph;
+pub mod find_symbol;
+pub mod docs_to_markdown;
+pub mod examples_only;
+pub mod browse_crates;
+pub mod api_surface;
+pub mod latest_version;
+pub mod crate_summary;
+pub mod std_docs;
+pub mod expand_crate_prefix;
+pub mod diff_crate_api;
+pub mod normalize_crate_name;
+pub mod fetch_repo_file;
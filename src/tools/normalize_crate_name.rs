@@ -0,0 +1,133 @@
+// src/tools/normalize_crate_name.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::future::Future;
+
+use crate::tools::query_rustdocs::{parse_registry_base, registry_get};
+use crate::tools::recommend_crate::{search_crates_io, CrateCandidate};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct NormalizeCrateNameArgs {
+    /// A crate name as the user wrote it, e.g. "Serde_JSON" or "tokio_util".
+    #[schemars(example = "Serde_JSON")]
+    name: String,
+}
+
+#[derive(Debug, Serialize)]
+struct NormalizeCrateNameResponse {
+    input: String,
+    exists: bool,
+    /// The name the crate is published under, when it exists.
+    canonical_name: Option<String>,
+    /// `canonical_name` differs from the input (case or `-`/`_`).
+    changed: bool,
+    latest_version: Option<String>,
+    /// Why the name was rejected without a lookup, e.g. invalid characters.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    problem: Option<String>,
+    /// Search hits for the name when no crate has it.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    suggestions: Vec<CrateCandidate>,
+}
+
+/// Search hits returned as suggestions.
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Why `name` can't be a crates.io crate name, if it can't.
+fn name_problem(name: &str) -> Option<String> {
+    if name.len() > 64 {
+        return Some("crate names are at most 64 characters".to_string());
+    }
+    if !name.chars().next().is_some_and(|c| c.is_ascii_alphabetic()) {
+        return Some("crate names start with an ASCII letter".to_string());
+    }
+    if let Some(c) = name.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '-' || *c == '_')) {
+        return Some(format!("'{}' is not allowed; crate names use ASCII letters, digits, '-' and '_'", c));
+    }
+    None
+}
+
+#[tool(
+    name = "normalize_crate_name",
+    description = "Checks a crate name against crates.io and returns the canonical published name (fixing case and -/_ mix-ups), or says no such crate exists and suggests similar ones. A cheap pre-check before query_rustdocs."
+)]
+pub async fn normalize_crate_name(
+    Parameters(args): Parameters<NormalizeCrateNameArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let input = args.name.trim().to_string();
+    if input.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("name is required.")]));
+    }
+    let cfg = crate::config::config();
+    let registry_base = match parse_registry_base(&cfg.registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let mut response = NormalizeCrateNameResponse {
+        input: input.clone(),
+        exists: false,
+        canonical_name: None,
+        changed: false,
+        latest_version: None,
+        problem: name_problem(&input),
+        suggestions: Vec::new(),
+    };
+
+    // the registry resolves names case- and -/_-insensitively and answers with the published spelling
+    if response.problem.is_none() {
+        let url = format!("{}/crates/{}", registry_base, input);
+        let resp = match registry_get(&client, &url, &format!("crates.io metadata for '{}'", input)).await {
+            Ok(r) => r,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.to_string())])),
+        };
+        if resp.status().is_success() {
+            let v: serde_json::Value = resp.json().await.unwrap_or_default();
+            let krate = v.get("crate");
+            let canonical = krate
+                .and_then(|c| c.get("name"))
+                .and_then(|n| n.as_str())
+                .unwrap_or(&input)
+                .to_string();
+            response.exists = true;
+            response.changed = canonical != input;
+            response.latest_version = krate
+                .and_then(|c| c.get("max_stable_version").filter(|x| !x.is_null()).or_else(|| c.get("max_version")))
+                .and_then(|x| x.as_str())
+                .map(|s| s.to_string());
+            response.canonical_name = Some(canonical);
+        } else if resp.status() != reqwest::StatusCode::NOT_FOUND {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "registry returned {} for '{}'",
+                resp.status(),
+                input
+            ))]));
+        }
+    }
+
+    let query: String = input.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { ' ' }).collect();
+    if !response.exists && !query.trim().is_empty() {
+        match search_crates_io(&client, &registry_base, query.trim(), MAX_SUGGESTIONS).await {
+            Ok(mut hits) => {
+                hits.truncate(MAX_SUGGESTIONS);
+                response.suggestions = hits;
+            }
+            Err(e) => tracing::debug!(input = %input, "suggestion search failed: {}", e),
+        }
+    }
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
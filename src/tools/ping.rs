@@ -0,0 +1,96 @@
+// src/tools/ping.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use reqwest::Client;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tokio::time::timeout;
+
+use crate::config::{config, Config};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct PingArgs {
+    /// Set to false to skip the outbound crates.io reachability check.
+    #[serde(default)]
+    #[schemars(description = "Whether to check crates.io reachability (default true)")]
+    check_network: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+struct NetworkCheck {
+    target: String,
+    reachable: bool,
+    status: Option<u16>,
+    latency_ms: u128,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct PingResponse {
+    status: String,
+    version: String,
+    uptime_secs: u64,
+    tools_enabled: usize,
+    /// Effective server config (crate allow/deny policy).
+    config: &'static Config,
+    network: Option<NetworkCheck>,
+}
+
+/// HEAD crates.io with a short timeout so a dead network doesn't stall the ping.
+async fn check_crates_io(client: &Client) -> NetworkCheck {
+    let target = "https://crates.io/".to_string();
+    let started = Instant::now();
+    let (reachable, status, error) =
+        match timeout(Duration::from_secs(3), client.head(&target).send()).await {
+            Ok(Ok(resp)) => (true, Some(resp.status().as_u16()), None),
+            Ok(Err(e)) => (false, None, Some(format!("network error: {}", e))),
+            Err(_) => (false, None, Some("timeout after 3s".to_string())),
+        };
+
+    NetworkCheck {
+        target,
+        reachable,
+        status,
+        latency_ms: started.elapsed().as_millis(),
+        error,
+    }
+}
+
+#[tool(
+    name = "ping",
+    description = "Health check: returns server uptime, version, enabled tool count, and whether crates.io is reachable."
+)]
+pub async fn ping(
+    Parameters(args): Parameters<PingArgs>,
+    uptime: Duration,
+    tools_enabled: usize,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let network = if args.check_network.unwrap_or(true) {
+        Some(check_crates_io(&client).await)
+    } else {
+        None
+    };
+
+    let response = PingResponse {
+        status: "ok".into(),
+        version: env!("CARGO_PKG_VERSION").into(),
+        uptime_secs: uptime.as_secs(),
+        tools_enabled,
+        config: config(),
+        network,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
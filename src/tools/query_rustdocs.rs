@@ -1,6 +1,7 @@
 
 use rmcp::handler::server::tool::Parameters;
-use rmcp::model::{CallToolResult, Content};
+use rmcp::model::{CallToolResult, Content, ProgressNotificationParam};
+use rmcp::service::{RequestContext, RoleServer};
 use rmcp::ErrorData;
 
 use serde::{Deserialize, Serialize};
@@ -11,55 +12,709 @@ use rmcp::serde_json;
 use reqwest::Client;
 use scraper::{Html, Selector};
 use std::collections::{HashSet, VecDeque};
-use std::time::Duration;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::Semaphore;
 use tokio::time::timeout;
+use tracing::Instrument;
 
 /// Tool arguments: LLM should supply crate names it intends to use.
 /// Optionally include a prompt for context.
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct QueryRustDocsArgs {
     #[serde(default)]
+    #[schemars(example = "async HTTP server that stores sessions in Redis")]
     pub prompt: Option<String>,
 
-    /// Crates the LLM decided to use, e.g. ["ggez","rand"].
+    /// Crates the LLM decided to use, e.g. ["ggez","rand"]. Append `@req,req` to compare
+    /// specific versions, e.g. "tokio@1.35,1.0"; those come back under `version_groups`.
+    #[schemars(example = ["tokio", "serde"])]
     pub crates: Vec<String>,
 
     /// Maximum docs.rs pages to fetch per crate (safety cap).
     #[serde(default)]
+    #[schemars(example = 20)]
     pub docs_max_pages: Option<usize>,
 
+    /// Cap on docs.rs pages crawled across all crates in this call.
+    #[serde(default)]
+    pub total_max_pages: Option<usize>,
+
+    /// How many links deep the docs.rs crawl may go from the crate root (default 3).
+    #[serde(default)]
+    pub docs_max_depth: Option<usize>,
+
     /// Maximum example files to fetch from GitHub (safety cap).
     #[serde(default)]
     pub examples_max_files: Option<usize>,
+
+    /// Also scrape the repository's `tests/` directory for usage examples.
+    /// Shares the `examples_max_files` cap with `examples/`.
+    #[serde(default)]
+    pub include_tests: Option<bool>,
+
+    /// Only fetch example/test files whose path contains this text (case-insensitive),
+    /// e.g. "async" or "server".
+    #[serde(default)]
+    #[schemars(example = "server")]
+    pub example_filter: Option<String>,
+
+    /// 1-based page of `docs_anchor_items`/`docs_items`/`docs_code_snippets`/`symbols`/
+    /// `function_signatures` to return.
+    /// Pagination is off unless `page` or `page_size` is given.
+    #[serde(default)]
+    pub page: Option<usize>,
+
+    /// Items per page for the paginated lists (default 50).
+    #[serde(default)]
+    pub page_size: Option<usize>,
+
+    /// A type inside the crate to inspect, e.g. "sync::Mutex" or "sync/struct.Mutex.html".
+    /// When it resolves to a struct/enum page its trait implementations are returned.
+    #[serde(default)]
+    #[schemars(example = "sync::Mutex")]
+    pub item_path: Option<String>,
+
+    /// Use docs.rs's rustdoc JSON build for symbols, signatures and doc text when it exists.
+    /// Falls back to HTML scraping when the JSON isn't available for this version.
+    #[serde(default)]
+    pub prefer_rustdoc_json: Option<bool>,
+
+    /// How many crates are enriched at the same time (default 3).
+    #[serde(default)]
+    pub max_concurrent_crates: Option<usize>,
+
+    /// Skip docs.rs/GitHub pages disallowed by the host's robots.txt (default true).
+    #[serde(default)]
+    pub respect_robots: Option<bool>,
+
+    /// Condense `docs_text_aggregate` to about this many characters, favouring the
+    /// crate root page's prose and its first example.
+    #[serde(default)]
+    pub summary_char_budget: Option<usize>,
+
+    /// Components to gather: any of "version", "docs", "readme", "examples", "snippets".
+    /// Everything is fetched when omitted. The version is always resolved.
+    #[serde(default)]
+    #[schemars(example = ["version", "docs", "snippets"])]
+    pub fetch: Option<Vec<String>>,
+
+    /// Serve crates only from the cache and never touch the network.
+    /// Defaults to `MCP_OFFLINE`.
+    #[serde(default)]
+    pub offline: Option<bool>,
+
+    /// Only return code snippets that look like complete Rust (balanced delimiters,
+    /// not marked `ignore`/`compile_fail`, no inline fragments).
+    #[serde(default)]
+    pub code_only: Option<bool>,
+
+    /// Extra snippet languages to return besides Rust, e.g. ["toml", "sh"].
+    /// Only Rust snippets are returned when omitted.
+    #[serde(default)]
+    pub snippet_languages: Option<Vec<String>>,
+
+    /// Registry API base with the crates.io v1 shape, for private registries or mirrors.
+    /// Defaults to `MCP_REGISTRY_BASE`, then "https://crates.io/api/v1".
+    #[serde(default)]
+    pub registry_base: Option<String>,
+
+    /// `crawl_cursor` from a previous response: continue that crate's docs.rs crawl
+    /// where it stopped instead of starting from the root.
+    #[serde(default)]
+    pub resume_cursor: Option<String>,
+
+    /// Also return the flat `docs_anchor_items` strings next to `docs_items` (default true).
+    #[serde(default)]
+    pub flat_anchor_items: Option<bool>,
+
+    /// When `crates` is empty, search crates.io with keywords from `prompt` and look
+    /// up the best few matches instead of returning the "no crates" help.
+    #[serde(default)]
+    pub auto_select: Option<bool>,
+
+    /// Minimum milliseconds between docs.rs requests to the same host
+    /// (default `MCP_CRAWL_DELAY_MS`, then 100). 0 disables the delay.
+    #[serde(default)]
+    pub crawl_delay_ms: Option<u64>,
+
+    /// Fetch only the crate root docs page (overview + top example) and skip the crawl.
+    #[serde(default)]
+    pub shallow: Option<bool>,
+
+    /// Truncate `github_readme` to this many characters (default 20000).
+    #[serde(default)]
+    pub max_readme_chars: Option<usize>,
+
+    /// Fetch nothing; return the URLs each crate would hit under the current args and caps.
+    #[serde(default)]
+    pub dry_run: Option<bool>,
+
+    /// Include the start of the response body in errors for unexpected registry/docs.rs statuses.
+    #[serde(default)]
+    pub verbose_errors: Option<bool>,
+
+    /// Docs host serving docs.rs-style URLs, for self-hosted rustdoc mirrors.
+    /// Defaults to `MCP_DOCS_BASE`, then "https://docs.rs".
+    #[serde(default)]
+    pub docs_base: Option<String>,
+
+    /// Return `item_docs` (each item's own doc text, capped per item) instead of
+    /// one `docs_text_aggregate` blob.
+    #[serde(default)]
+    pub per_item_docs: Option<bool>,
+
+    /// Add `fetched_pages` to each result: every docs page the crawl tried, with
+    /// final URL, HTTP status and size.
+    #[serde(default)]
+    pub debug: Option<bool>,
+
+    /// Put the JSON between `<<<QUERY_RUSTDOCS_JSON_BEGIN>>>` and `<<<QUERY_RUSTDOCS_JSON_END>>>`
+    /// lines so clients can cut it out even if it ends up wrapped in other text.
+    #[serde(default)]
+    pub json_markers: Option<bool>,
+
+    /// The Rust edition the user's project uses. Crates whose selected version
+    /// declares a newer edition get an "edition_too_new" warning.
+    #[serde(default)]
+    #[schemars(example = "2018")]
+    pub edition: Option<String>,
+
+    /// Add `related_crates`: up to five other popular crates from the same crates.io
+    /// category (or keyword), for suggesting alternatives. One extra registry request per crate.
+    #[serde(default)]
+    pub related_crates: Option<bool>,
+
+    /// Upper bound on the response size in bytes. Larger responses are trimmed in a fixed
+    /// order (example file contents, extra snippets, all snippets, docs text, READMEs),
+    /// with a "response_trimmed" warning per step.
+    #[serde(default)]
+    #[schemars(example = 200000)]
+    pub max_response_bytes: Option<usize>,
 }
 
 /// Per-crate aggregated result returned to the LLM.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CrateResult {
     pub name: String,
+    /// The version requirement asked for with `name@req`; `None` means "latest".
+    pub requested_version: Option<String>,
     pub latest_version: String,
+    /// Why `latest_version` was picked, e.g. "highest stable non-yanked version".
+    #[serde(default)]
+    pub version_selection_reason: String,
     pub dependency_line: String,
+    /// Dependency line enabling an umbrella feature (e.g. tokio's "full") when
+    /// the crate enables nothing by default.
+    pub recommended_dependency_line: Option<String>,
+    /// When `latest_version` was published (RFC 3339).
+    pub published_at: Option<String>,
+    /// `package.edition` of `latest_version`, when the registry reports it.
+    #[serde(default)]
+    pub edition: Option<String>,
+    /// Days between `published_at` and this response.
+    pub days_since_publish: Option<i64>,
     pub description: Option<String>,
     pub repository: Option<String>,
     pub crates_io_documentation: Option<String>,
     pub docs_rs_root: Option<String>,
+    /// Stable links the model can cite even when the crawl came back thin.
+    pub docs_rs_latest_url: Option<String>,
+    /// The name docs.rs files the crate under when it isn't `name`: the other
+    /// hyphen/underscore spelling, found after the root page 404'd.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs_rs_name: Option<String>,
+    pub lib_rs_url: Option<String>,
     pub docs_rs_pages_count: usize,
+    /// Set when the docs.rs crawl stopped with pages still queued; pass it back as
+    /// `resume_cursor` to keep crawling.
+    pub crawl_cursor: Option<String>,
     pub docs_anchor_items: Vec<String>,
+    /// Documented items with their kind ("struct", "fn", "trait", ...) so callers can filter.
+    pub docs_items: Vec<DocItem>,
+    /// "kind path" entries, e.g. "struct tokio::sync::Mutex" (rustdoc JSON only).
+    pub symbols: Vec<String>,
+    /// Rendered `fn` signatures (rustdoc JSON only).
+    pub function_signatures: Vec<String>,
+    /// "rustdoc_json" or "html" depending on where the docs fields came from.
+    pub docs_source: Option<String>,
     pub docs_text_aggregate: Option<String>,
+    /// Doc text per item, sorted by path (only with `per_item_docs`).
+    #[serde(default)]
+    pub item_docs: Vec<ItemDoc>,
     pub docs_code_snippets: Vec<String>,
+    /// Trait impl headers (e.g. "impl<T: Clone> Clone for Foo<T>") for `item_path`.
+    pub trait_impls: Vec<String>,
+    /// Directory inside the repository whose Cargo.toml declares this crate
+    /// ("" for the repo root). `None` when it couldn't be confirmed.
+    pub repository_subpath: Option<String>,
+    /// README text, from GitHub or (for crates without a GitHub repository) crates.io.
     pub github_readme: Option<String>,
-    pub github_examples: Vec<(String, String)>,
+    /// Where `github_readme` came from: "github" or "crates.io".
+    #[serde(default)]
+    pub readme_source: Option<String>,
+    pub github_examples: Vec<ExampleFile>,
+    /// Integration test files from `tests/` (only with `include_tests`).
+    pub github_tests: Vec<ExampleFile>,
+    /// Versions of this crate that were yanked, newest first.
+    #[serde(default)]
+    pub yanked_versions: Vec<String>,
+    /// Other crates in this crate's first category (or keyword), most downloaded first
+    /// (only with `related_crates`).
+    #[serde(default)]
+    pub related_crates: Vec<String>,
+    /// Each docs page request the crawl made, in order (only with `debug`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fetched_pages: Vec<FetchedPage>,
     pub errors: Vec<String>,
 }
 
+impl CrateResult {
+    /// Sort the item lists (deduplicating repeats) so the same crate and options give
+    /// the same output, and responses can be diffed and snapshotted.
+    fn sort_lists(&mut self) {
+        self.docs_anchor_items.sort();
+        self.docs_anchor_items.dedup();
+        self.docs_items.sort_by(|a, b| (&a.path, &a.kind).cmp(&(&b.path, &b.kind)));
+        self.symbols.sort();
+        self.function_signatures.sort();
+        self.item_docs.sort_by(|a, b| a.path.cmp(&b.path));
+        self.item_docs.dedup_by(|a, b| a.path == b.path);
+    }
+}
+
+/// One docs page request and how it went.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchedPage {
+    pub requested_url: String,
+    /// URL after redirects; the requested URL when no response came back.
+    pub url: String,
+    /// `None` when the request never got a response (timeout, network error, robots.txt).
+    pub status: Option<u16>,
+    /// Decoded body size; 0 for failed requests.
+    pub bytes: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// One file from the crate's repository: an example, a test or a file fetched by path.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExampleFile {
+    /// Path inside the repository, e.g. "examples/echo.rs".
+    pub path: String,
+    pub content: String,
+    /// Size of the whole file in bytes, before any truncation.
+    pub bytes: usize,
+    /// `content` was cut at `MAX_EXAMPLE_FILE_CHARS`.
+    pub truncated: bool,
+}
+
+/// Longest `ExampleFile::content`, in characters.
+const MAX_EXAMPLE_FILE_CHARS: usize = 40_000;
+
+impl ExampleFile {
+    pub(crate) fn new(path: String, mut content: String) -> Self {
+        let bytes = content.len();
+        let truncated = match content.char_indices().nth(MAX_EXAMPLE_FILE_CHARS) {
+            Some((idx, _)) => {
+                content.truncate(idx);
+                true
+            }
+            None => false,
+        };
+        ExampleFile { path, content, bytes, truncated }
+    }
+}
+
+/// One documented item of a crate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocItem {
+    pub name: String,
+    /// rustdoc item kind: "struct", "enum", "fn", "trait", "macro", "mod", ...
+    pub kind: String,
+    /// docs.rs page relative to the crate docs root (HTML) or the `::` path (rustdoc JSON).
+    pub path: String,
+}
+
+/// The doc comment of one item, as rendered text.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemDoc {
+    /// Full `::` path, e.g. "tokio::sync::Mutex".
+    pub path: String,
+    pub doc_text: String,
+}
+
+/// Longest `ItemDoc::doc_text`, in characters.
+const ITEM_DOC_MAX_CHARS: usize = 1500;
+
+/// Every requested version of one crate, in the order they were asked for.
+#[derive(Debug, Serialize)]
+pub struct CrateVersionGroup {
+    pub name: String,
+    pub versions: Vec<CrateResult>,
+}
+
+/// Split "tokio@1.35,1.0" into ("tokio", ["1.35", "1.0"]); no `@` means latest only.
+fn parse_crate_spec(entry: &str) -> (String, Vec<String>) {
+    match entry.trim().split_once('@') {
+        Some((name, versions)) => (
+            name.trim().to_string(),
+            versions
+                .split(',')
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+                .collect(),
+        ),
+        None => (entry.trim().to_string(), Vec::new()),
+    }
+}
+
+/// A crate `auto_select` picked from the prompt, and why.
+#[derive(Debug, Serialize)]
+pub struct AutoSelectedCrate {
+    pub name: String,
+    pub score: f64,
+    pub reason: String,
+}
+
+/// How many crates `auto_select` looks up.
+const AUTO_SELECT_COUNT: usize = 3;
+
 #[derive(Debug, Serialize)]
 pub struct QueryRustDocsResponse {
+    /// `SCHEMA_VERSION` of this payload.
+    pub schema_version: u32,
+    /// Id of this tool call, also on every server log line it produced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
     pub query_prompt: Option<String>,
+    /// When this response was put together (RFC 3339); cached crates may be older.
+    pub generated_at: String,
     pub tool_usage_hint: String,
+    /// Crates asked for at several versions ("tokio@1.35,1.0"), one result per version.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub version_groups: Vec<CrateVersionGroup>,
+    /// Ready-to-paste `[dependencies]` section for every crate that resolved,
+    /// using `recommended_dependency_line` where there is one.
+    pub cargo_dependencies_block: String,
+    /// One entry per requested crate, in the order `crates` listed them.
+    /// List fields inside each result are sorted so identical calls give identical output.
     pub results: Vec<CrateResult>,
+    /// `warning_details` flattened to "crate: message" strings.
+    pub warnings: Vec<String>,
+    pub warning_details: Vec<Warning>,
+    /// Set when any crate has more anchor items or snippets past the returned page.
+    pub next_page: Option<usize>,
+    /// Crates chosen from the prompt when `auto_select` was used.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_selected: Option<Vec<AutoSelectedCrate>>,
+}
+
+/// A response warning with enough structure for tooling to act on it.
+#[derive(Debug, Clone, Serialize)]
+pub struct Warning {
+    pub crate_name: Option<String>,
+    /// "info" (nothing missing), "warning" (partial result) or "error" (a component failed).
+    pub severity: String,
+    /// Short machine-readable category, e.g. "stale_version", "docs_unavailable".
+    pub kind: String,
+    pub message: String,
+}
+
+impl Warning {
+    fn new(crate_name: Option<&str>, severity: &str, kind: &str, message: String) -> Self {
+        Warning {
+            crate_name: crate_name.map(|c| c.to_string()),
+            severity: severity.to_string(),
+            kind: kind.to_string(),
+            message,
+        }
+    }
+
+    /// Severity and kind of one `CrateResult.errors` entry. `fatal` is set when the
+    /// crate didn't resolve at all.
+    fn from_crate_error(crate_name: &str, message: &str, fatal: bool) -> Self {
+        let (severity, kind) = if fatal {
+            ("error", "crate_unavailable")
+        } else if message.starts_with("rustdoc JSON unavailable") {
+            ("info", "rustdoc_json_fallback")
+        } else if message.starts_with("resume_cursor was for") {
+            ("info", "resume_restarted")
+        } else if message.starts_with("total_max_pages budget") {
+            ("warning", "page_budget_exhausted")
+        } else if message.starts_with("Failed to fetch docs.rs") {
+            ("error", "docs_unavailable")
+        } else if message.starts_with("docs.rs pages parsed but no content") {
+            ("error", "docs_unparsed")
+        } else if message.starts_with("recently yanked") {
+            ("warning", "recent_yank")
+        } else if message.starts_with("Could not fetch README") {
+            ("warning", "readme_unavailable")
+        } else {
+            ("warning", "partial_result")
+        };
+        Warning::new(Some(crate_name), severity, kind, message.to_string())
+    }
+
+    /// The pre-structured string form.
+    fn flatten(&self) -> String {
+        match self.crate_name {
+            Some(ref c) => format!("{}: {}", c, self.message),
+            None => self.message.clone(),
+        }
+    }
+}
+
+/// One request `query_rustdocs` would make. `{version}`, `{owner}`, `{repo}` and
+/// `{branch}` stand for values only known once earlier requests have answered.
+#[derive(Debug, Serialize)]
+pub struct PlannedRequest {
+    pub purpose: String,
+    pub url: String,
+}
+
+/// What a dry run would do for one crate.
+#[derive(Debug, Serialize)]
+pub struct CrateFetchPlan {
+    pub name: String,
+    pub requested_version: Option<String>,
+    /// Why nothing would be fetched (policy, cache hit, offline).
+    pub skipped: Option<String>,
+    pub requests: Vec<PlannedRequest>,
+    /// Upper bound on requests for this crate, counting every crawled page and file.
+    pub max_requests: usize,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueryRustDocsDryRun {
+    pub schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub dry_run: bool,
+    pub crates: Vec<CrateFetchPlan>,
+    /// Sum of `max_requests`, clipped by `total_max_pages`.
+    pub estimated_max_requests: usize,
     pub warnings: Vec<String>,
 }
 
+// -------------------- helpers: fetch selection ----------------------------------
+
+/// Which parts of a crate's info `enrich_crate_full` should gather.
+#[derive(Debug, Clone, Copy)]
+struct FetchPlan {
+    docs: bool,
+    snippets: bool,
+    readme: bool,
+    examples: bool,
+}
+
+impl FetchPlan {
+    fn all() -> Self {
+        FetchPlan { docs: true, snippets: true, readme: true, examples: true }
+    }
+
+    /// Build a plan from the `fetch` arg. Unknown component names are returned
+    /// alongside so the caller can warn about them.
+    fn from_arg(fetch: Option<&[String]>) -> (Self, Vec<String>) {
+        let Some(items) = fetch else {
+            return (Self::all(), Vec::new());
+        };
+        let mut plan = FetchPlan { docs: false, snippets: false, readme: false, examples: false };
+        let mut unknown = Vec::new();
+        for item in items {
+            match item.trim().to_lowercase().as_str() {
+                "version" => {}
+                "docs" => plan.docs = true,
+                "snippets" => plan.snippets = true,
+                "readme" => plan.readme = true,
+                "examples" => plan.examples = true,
+                _ => unknown.push(item.clone()),
+            }
+        }
+        (plan, unknown)
+    }
+
+    fn needs_docs_crawl(&self) -> bool {
+        self.docs || self.snippets
+    }
+
+    fn needs_github(&self) -> bool {
+        self.readme || self.examples
+    }
+}
+
+impl EnrichOptions {
+    /// Cache key for `crate_name` under these options; results gathered with
+    /// different knobs are cached separately.
+    fn cache_key(&self, crate_name: &str, pinned: Option<&str>) -> String {
+        let f = self.fetch;
+        format!(
+            "{}@{}|{}|{}|docs={},snippets={},readme={},examples={}|pages={},depth={},files={},tests={},filter={}|item={}|json={}|summary={}|code_only={}|langs={}|shallow={}|readme_chars={}|verbose_errors={}|per_item_docs={}|debug={}|related={}|robots={}|total_pages={}",
+            crate_name,
+            pinned.unwrap_or("latest"),
+            self.registry_base,
+            self.docs_base,
+            f.docs,
+            f.snippets,
+            f.readme,
+            f.examples,
+            self.docs_max_pages,
+            self.docs_max_depth,
+            self.examples_max_files,
+            self.include_tests,
+            self.example_filter.as_deref().unwrap_or(""),
+            self.item_path.as_deref().unwrap_or(""),
+            self.prefer_rustdoc_json,
+            self.summary_char_budget.map(|n| n.to_string()).unwrap_or_default(),
+            self.code_only,
+            self.snippet_languages.join(","),
+            self.shallow,
+            self.max_readme_chars,
+            self.verbose_errors,
+            self.per_item_docs,
+            self.debug,
+            self.related_crates,
+            self.respect_robots,
+            self.total_max_pages.map(|n| n.to_string()).unwrap_or_default(),
+        )
+    }
+}
+
+/// Look `crate_name` up in the cache. Online only fresh exact-option hits count;
+/// offline any stored copy of the crate is better than nothing (unless a version was pinned).
+fn cached_crate_result(crate_name: &str, pinned: Option<&str>, opts: &EnrichOptions) -> Option<CrateResult> {
+    let cache = crate::cache::cache();
+    let key = opts.cache_key(crate_name, pinned);
+    let entry = match cache.get(&key) {
+        Some(e) if opts.offline || e.age_secs() <= crate::config::config().cache_ttl_secs => Some(e),
+        _ if opts.offline && pinned.is_none() => cache.latest_for_crate(crate_name),
+        _ => None,
+    }?;
+    serde_json::from_value(entry.value).ok()
+}
+
+/// Per-call knobs shared by every crate enriched in one `query_rustdocs` call.
+#[derive(Debug, Clone)]
+struct EnrichOptions {
+    docs_max_pages: usize,
+    docs_max_depth: usize,
+    examples_max_files: usize,
+    include_tests: bool,
+    /// Lowercased path substring example files must contain.
+    example_filter: Option<String>,
+    fetch: FetchPlan,
+    item_path: Option<String>,
+    prefer_rustdoc_json: bool,
+    respect_robots: bool,
+    summary_char_budget: Option<usize>,
+    /// Split docs text per item instead of aggregating it.
+    per_item_docs: bool,
+    /// Only answer from the cache.
+    offline: bool,
+    /// Only keep snippets that look like complete, compilable Rust.
+    code_only: bool,
+    /// Registry API base, e.g. "https://crates.io/api/v1" (no trailing slash).
+    registry_base: String,
+    /// Docs host, e.g. "https://docs.rs" (no trailing slash).
+    docs_base: String,
+    /// Crawl state to pick up from (applies only to the crate it names).
+    resume: Option<CrawlCursor>,
+    /// Minimum gap between docs.rs requests to the same host.
+    crawl_delay: Duration,
+    /// Stop after the crate root page.
+    shallow: bool,
+    /// Cap on `github_readme` length in characters.
+    max_readme_chars: usize,
+    /// Quote error response bodies in `errors`.
+    verbose_errors: bool,
+    /// Report every crawled page in `fetched_pages`.
+    debug: bool,
+    /// Look up `related_crates`.
+    related_crates: bool,
+    /// Non-Rust snippet languages to keep as well (normalized, e.g. "toml", "sh").
+    snippet_languages: Vec<String>,
+    /// Pages left in the call-wide `total_max_pages` budget, shared by every crate.
+    page_budget: Option<Arc<AtomicUsize>>,
+    /// The call's `total_max_pages` as given, for the cache key.
+    total_max_pages: Option<usize>,
+}
+
+/// What a docs.rs crawl collected.
+#[derive(Debug, Default)]
+struct CrawlOutcome {
+    pages: Vec<String>,
+    visited: Vec<String>,
+    /// The shared page budget ran out before this crawl was done.
+    budget_exhausted: bool,
+    /// Docs root the crawl settled on after following docs.rs redirects.
+    base: Option<String>,
+    /// Paths still queued when the crawl stopped.
+    remaining: Vec<(String, usize)>,
+    /// Every page request made, successful or not.
+    fetched: Vec<FetchedPage>,
+    /// Set when the docs were only found under the alternate hyphen/underscore name.
+    docs_name: Option<String>,
+}
+
+/// Everything needed to continue a docs.rs crawl in a later call.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CrawlCursor {
+    crate_name: String,
+    version: String,
+    base: String,
+    visited: Vec<String>,
+    queue: Vec<(String, usize)>,
+}
+
+impl CrawlCursor {
+    /// Gzipped JSON, hex encoded, so clients can treat it as an opaque token.
+    fn encode(&self) -> Option<String> {
+        use std::io::Write;
+        let json = serde_json::to_vec(self).ok()?;
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(&json).ok()?;
+        let bytes = gz.finish().ok()?;
+        Some(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+
+    fn decode(token: &str) -> Result<Self, String> {
+        let token = token.trim();
+        if token.len() % 2 != 0 || !token.is_ascii() {
+            return Err("resume_cursor is not a valid crawl cursor".to_string());
+        }
+        let bytes = (0..token.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&token[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| "resume_cursor is not a valid crawl cursor".to_string())?;
+        let mut json = Vec::new();
+        flate2::read::GzDecoder::new(&bytes[..])
+            .read_to_end(&mut json)
+            .map_err(|_| "resume_cursor is not a valid crawl cursor".to_string())?;
+        serde_json::from_slice(&json).map_err(|e| format!("resume_cursor could not be read: {}", e))
+    }
+}
+
+// -------------------- helpers: pagination ---------------------------------------
+
+/// Keep only the 1-based `page` of `items`. Returns true if more items follow.
+fn paginate<T>(items: &mut Vec<T>, page: usize, page_size: usize) -> bool {
+    let start = page.saturating_sub(1).saturating_mul(page_size);
+    let total = items.len();
+    if start >= total {
+        items.clear();
+        return false;
+    }
+    let end = std::cmp::min(start + page_size, total);
+    items.truncate(end);
+    items.drain(..start);
+    end < total
+}
+
 // -------------------- helpers: version selection ------------------------------
 
 /// Parse a version string into vector of numeric segments and a prerelease flag.
@@ -128,17 +783,178 @@ fn version_is_greater(a: &str, b: &str) -> bool {
 
 // -------------------- helpers: crates.io metadata --------------------------------
 
+/// Why a registry lookup failed. Rate limiting is kept apart so the caller can tell
+/// the user to slow down instead of implying the crate doesn't exist.
+#[derive(Debug)]
+pub(crate) enum RegistryError {
+    RateLimited { retry_after_secs: u64 },
+    Other(String),
+}
+
+impl std::fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryError::RateLimited { retry_after_secs } => write!(
+                f,
+                "the registry is rate limiting requests (429); slow down and retry in about {}s",
+                retry_after_secs
+            ),
+            RegistryError::Other(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl From<String> for RegistryError {
+    fn from(msg: String) -> Self {
+        RegistryError::Other(msg)
+    }
+}
+
+/// Longest `Retry-After` we are willing to sleep through inside a tool call.
+const MAX_RETRY_AFTER_SECS: u64 = 30;
+
+/// `Retry-After` in seconds (delta form only; HTTP dates fall back to 2s).
+fn retry_after_secs(headers: &reqwest::header::HeaderMap) -> u64 {
+    headers
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(2)
+}
+
+/// GET `url`, and on a 429 wait out `Retry-After` (capped) and try once more.
+pub(crate) async fn registry_get(client: &Client, url: &str, what: &str) -> Result<reqwest::Response, RegistryError> {
+    let send = || async {
+        crate::rate_limit::throttle(url).await;
+        // time to response headers; with the shared client, calls after the first reuse a
+        // pooled connection and skip the TCP/TLS handshake, which shows up here
+        let started = Instant::now();
+        let resp = timeout(Duration::from_secs(12), client.get(url).send())
+            .await
+            .map_err(|_| format!("timeout fetching {}", what))?
+            .map_err(|e| format!("network error fetching {}: {}", what, e))?;
+        tracing::debug!(url, elapsed_ms = started.elapsed().as_millis() as u64, "registry request");
+        Ok::<_, String>(resp)
+    };
+    let resp = send().await?;
+    if resp.status() != reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Ok(resp);
+    }
+    let wait = retry_after_secs(resp.headers());
+    if wait > MAX_RETRY_AFTER_SECS {
+        return Err(RegistryError::RateLimited { retry_after_secs: wait });
+    }
+    tracing::warn!(url, wait, "rate limited by registry, retrying once");
+    tokio::time::sleep(Duration::from_secs(wait)).await;
+    let resp = send().await?;
+    if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(RegistryError::RateLimited { retry_after_secs: retry_after_secs(resp.headers()) });
+    }
+    Ok(resp)
+}
+
+/// Longest body snippet `verbose_errors` quotes from a failed response.
+const ERROR_BODY_SNIPPET_CHARS: usize = 500;
+
+/// The status of a failed response, followed with `verbose` by the start of its body.
+async fn describe_error_response(resp: reqwest::Response, verbose: bool) -> String {
+    let status = resp.status();
+    if !verbose {
+        return status.to_string();
+    }
+    match resp.text().await {
+        Ok(body) if !body.trim().is_empty() => {
+            format!("{} (body: {})", status, truncate_chars(body.trim(), ERROR_BODY_SNIPPET_CHARS))
+        }
+        _ => status.to_string(),
+    }
+}
+
+/// Most pages of a paginated versions listing read before picking from what we have.
+const MAX_VERSION_PAGES: usize = 20;
+
+/// Absolute URL of the next page of a registry listing from its `meta.next_page`,
+/// which crates.io sends as a query string ("?per_page=100&seek=...").
+pub(crate) fn registry_next_page(url: &str, body: &serde_json::Value) -> Option<String> {
+    let next = body.pointer("/meta/next_page")?.as_str()?.trim();
+    if next.is_empty() {
+        return None;
+    }
+    if next.starts_with("http://") || next.starts_with("https://") {
+        return Some(next.to_string());
+    }
+    let base = url.split('?').next().unwrap_or(url);
+    Some(format!("{}?{}", base, next.trim_start_matches('?')))
+}
+
+/// Every version object of the registry versions listing at `url`: those on `first` (the
+/// first page, already fetched by the caller) plus the pages `meta.next_page` leads to, up to
+/// `MAX_VERSION_PAGES`. A later page that fails ends the walk with the versions read so far.
+pub(crate) async fn collect_version_pages(
+    client: &Client,
+    url: &str,
+    first: &serde_json::Value,
+    crate_name: &str,
+) -> Result<Vec<serde_json::Value>, RegistryError> {
+    let mut versions = first.get("versions").and_then(|x| x.as_array()).cloned().unwrap_or_default();
+    let mut next = registry_next_page(url, first);
+    let mut pages = 1;
+    while let Some(page_url) = next.take() {
+        if pages >= MAX_VERSION_PAGES {
+            tracing::warn!(crate_name, pages, "stopped following crates.io version pages at the cap");
+            break;
+        }
+        let what = format!("crates.io versions page {} for '{}'", pages + 1, crate_name);
+        let resp = registry_get(client, &page_url, &what).await?;
+        if !resp.status().is_success() {
+            // pick from what we have rather than failing the whole lookup
+            tracing::warn!(crate_name, status = %resp.status(), "could not fetch {}", what);
+            break;
+        }
+        let page: serde_json::Value = resp.json().await.map_err(|e| format!("invalid JSON from {}: {}", what, e))?;
+        versions.extend(page.get("versions").and_then(|x| x.as_array()).into_iter().flatten().cloned());
+        next = registry_next_page(url, &page);
+        pages += 1;
+    }
+    Ok(versions)
+}
+
+/// What `fetch_crates_io_best_version` learns about a crate.
+#[derive(Debug)]
+pub(crate) struct CrateMeta {
+    pub(crate) latest_version: String,
+    description: Option<String>,
+    repository_or_docs: Option<String>,
+    /// (version, publish time) of every yanked release, newest first.
+    yanked: Vec<(String, Option<String>)>,
+    /// How `latest_version` was chosen.
+    pub(crate) selection_reason: String,
+    /// crates.io category slugs, e.g. "web-programming::http-client".
+    categories: Vec<String>,
+    keywords: Vec<String>,
+}
+
+/// String ids listed under `key` of a registry crate object ("categories", "keywords").
+fn registry_id_list(crate_obj: Option<&serde_json::Value>, key: &str) -> Vec<String> {
+    crate_obj
+        .and_then(|c| c.get(key))
+        .and_then(|l| l.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|id| id.as_str().map(|s| s.to_string()))
+        .collect()
+}
+
 /// Fetch versions list and pick highest non-yanked version (preferring stable).
-async fn fetch_crates_io_best_version(
+pub(crate) async fn fetch_crates_io_best_version(
     client: &Client,
+    registry_base: &str,
     crate_name: &str,
-) -> Result<(String, Option<String>, Option<String>), String> {
+    verbose_errors: bool,
+) -> Result<CrateMeta, RegistryError> {
     // First try versions endpoint
-    let url_versions = format!("https://crates.io/api/v1/crates/{}/versions", crate_name);
-    let resp = timeout(Duration::from_secs(12), client.get(&url_versions).send())
-        .await
-        .map_err(|_| format!("timeout fetching crates.io versions for '{}'", crate_name))?
-        .map_err(|e| format!("network error fetching crates.io versions for '{}': {}", crate_name, e))?;
+    let url_versions = format!("{}/crates/{}/versions", registry_base, crate_name);
+    let resp = registry_get(client, &url_versions, &format!("crates.io versions for '{}'", crate_name)).await?;
 
     if resp.status().is_success() {
         let v: serde_json::Value = resp
@@ -146,17 +962,24 @@ async fn fetch_crates_io_best_version(
             .await
             .map_err(|e| format!("invalid JSON from crates.io versions for '{}': {}", crate_name, e))?;
 
-        if let Some(arr) = v.get("versions").and_then(|x| x.as_array()) {
+        if v.get("versions").is_some_and(|x| x.is_array()) {
+            let arr = collect_version_pages(client, &url_versions, &v, crate_name).await?;
+
             // iterate and pick best
             let mut best: Option<String> = None;
             let mut description: Option<String> = None;
             let mut repository_or_docs: Option<String> = None;
-            for ver in arr {
+            let mut yanked_versions = Vec::new();
+            let mut any_stable = false;
+            for ver in &arr {
                 if let Some(num) = ver.get("num").and_then(|n| n.as_str()) {
                     let yanked = ver.get("yanked").and_then(|y| y.as_bool()).unwrap_or(false);
                     if yanked {
+                        let created = ver.get("created_at").and_then(|c| c.as_str()).map(|c| c.to_string());
+                        yanked_versions.push((num.to_string(), created));
                         continue;
                     }
+                    any_stable |= !parse_version_numeric_and_prerelease(num).1;
                     if best.is_none() || version_is_greater(num, best.as_ref().unwrap()) {
                         best = Some(num.to_string());
                     }
@@ -173,11 +996,35 @@ async fn fetch_crates_io_best_version(
                     }
                 }
             }
+            yanked_versions.sort_by_key(|(v, _)| std::cmp::Reverse(semver::Version::parse(v).ok()));
+            let selection_reason = best.as_deref().map(|b| {
+                let newer_yanked: Vec<&str> = yanked_versions
+                    .iter()
+                    .map(|(v, _)| v.as_str())
+                    .filter(|v| version_is_greater(v, b))
+                    .collect();
+                let base = match (parse_version_numeric_and_prerelease(b).1, any_stable) {
+                    (false, _) => "highest stable non-yanked version",
+                    (true, true) => "highest non-yanked version, a prerelease newer than any stable release",
+                    (true, false) => "only prerelease versions available",
+                };
+                if newer_yanked.is_empty() {
+                    base.to_string()
+                } else {
+                    format!("{} (newer {} yanked)", base, newer_yanked.join(", "))
+                }
+            });
             // fallback to crate root if we didn't get repo or description
-            if best.is_some() {
+            if let Some(best) = best {
                 // fetch crate root to get repository/documentation fields if missing
-                let url_crate = format!("https://crates.io/api/v1/crates/{}", crate_name);
-                if let Ok(Ok(resp2)) = timeout(Duration::from_secs(10), client.get(&url_crate).send()).await {
+                let url_crate = format!("{}/crates/{}", registry_base, crate_name);
+                let resp2 = match registry_get(client, &url_crate, &format!("crates.io metadata for '{}'", crate_name)).await {
+                    Ok(r) => Some(r),
+                    // still rate limited after the retry: report it rather than quietly dropping the metadata
+                    Err(e @ RegistryError::RateLimited { .. }) => return Err(e),
+                    Err(_) => None,
+                };
+                if let Some(resp2) = resp2 {
                     if resp2.status().is_success() {
                         if let Ok(v2) = resp2.json::<serde_json::Value>().await {
                             if repository_or_docs.is_none() {
@@ -192,25 +1039,39 @@ async fn fetch_crates_io_best_version(
                             }
                             // also documentation field
                             let documentation_field = v2.get("crate").and_then(|c| c.get("documentation")).and_then(|s| s.as_str()).map(|s| s.to_string());
-                            return Ok((best.unwrap(), description, repository_or_docs.or(documentation_field)));
+                            return Ok(CrateMeta {
+                                latest_version: best,
+                                description,
+                                repository_or_docs: repository_or_docs.or(documentation_field),
+                                yanked: yanked_versions,
+                                selection_reason: selection_reason.unwrap_or_default(),
+                                categories: registry_id_list(v2.get("crate"), "categories"),
+                                keywords: registry_id_list(v2.get("crate"), "keywords"),
+                            });
                         }
                     }
                 }
                 // otherwise return what we have
-                return Ok((best.unwrap(), description, repository_or_docs));
+                return Ok(CrateMeta {
+                    latest_version: best,
+                    description,
+                    repository_or_docs,
+                    yanked: yanked_versions,
+                    selection_reason: selection_reason.unwrap_or_default(),
+                    categories: Vec::new(),
+                    keywords: Vec::new(),
+                });
             }
         }
     }
 
     // fallback: try crate root and take max_version/newest_version
-    let url = format!("https://crates.io/api/v1/crates/{}", crate_name);
-    let resp = timeout(Duration::from_secs(12), client.get(&url).send())
-        .await
-        .map_err(|_| format!("timeout fetching crates.io for '{}'", crate_name))?
-        .map_err(|e| format!("network error fetching crates.io for '{}': {}", crate_name, e))?;
+    let url = format!("{}/crates/{}", registry_base, crate_name);
+    let resp = registry_get(client, &url, &format!("crates.io for '{}'", crate_name)).await?;
 
     if !resp.status().is_success() {
-        return Err(format!("crates.io returned {} for '{}'", resp.status(), crate_name));
+        let status = describe_error_response(resp, verbose_errors).await;
+        return Err(format!("crates.io returned {} for '{}'", status, crate_name).into());
     }
 
     let v: serde_json::Value = resp
@@ -241,101 +1102,998 @@ async fn fetch_crates_io_best_version(
         .and_then(|d| d.as_str())
         .map(|s| s.to_string());
 
-    Ok((latest_version, description, repository.or(documentation)))
+    Ok(CrateMeta {
+        latest_version,
+        description,
+        repository_or_docs: repository.or(documentation),
+        yanked: Vec::new(),
+        selection_reason: "the registry's max_version for the crate (version list unavailable)".to_string(),
+        categories: registry_id_list(Some(crate_obj), "categories"),
+        keywords: registry_id_list(Some(crate_obj), "keywords"),
+    })
 }
 
-// -------------------- helpers: docs.rs crawling --------------------------------
+/// Most entries in `related_crates`.
+const RELATED_CRATES_MAX: usize = 5;
 
-fn normalize_docs_href(href: &str) -> String {
-    let mut s = href.to_string();
-    while s.starts_with("../") || s.starts_with("./") {
-        if s.starts_with("../") {
-            s = s.replacen("../", "", 1);
-        } else {
-            s = s.replacen("./", "", 1);
-        }
-    }
-    if let Some(idx) = s.find('#') {
-        s.truncate(idx);
+/// The most downloaded crates sharing `crate_name`'s first category, or its first
+/// keyword when it has no category, excluding the crate itself.
+async fn fetch_related_crates(
+    client: &Client,
+    registry_base: &str,
+    crate_name: &str,
+    categories: &[String],
+    keywords: &[String],
+) -> Result<Vec<String>, RegistryError> {
+    let (param, value) = match (categories.first(), keywords.first()) {
+        (Some(c), _) => ("category", c),
+        (None, Some(k)) => ("keyword", k),
+        (None, None) => return Ok(Vec::new()),
+    };
+    let per_page = (RELATED_CRATES_MAX + 1).to_string();
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/crates", registry_base),
+        [(param, value.as_str()), ("sort", "downloads"), ("per_page", per_page.as_str())],
+    )
+    .map_err(|e| format!("invalid registry URL: {}", e))?;
+    let what = format!("crates.io {} '{}' listing", param, value);
+    let resp = registry_get(client, url.as_str(), &what).await?;
+    if !resp.status().is_success() {
+        return Err(format!("crates.io returned {} for the {}", resp.status(), what).into());
     }
-    s.trim_start_matches('/').to_string()
+    let v: serde_json::Value = resp.json().await.map_err(|e| format!("invalid JSON from the {}: {}", what, e))?;
+    Ok(v.get("crates")
+        .and_then(|c| c.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.get("name").and_then(|n| n.as_str()))
+        .filter(|n| *n != crate_name)
+        .take(RELATED_CRATES_MAX)
+        .map(|n| n.to_string())
+        .collect())
 }
 
-async fn fetch_docs_page(client: &Client, crate_name: &str, version: &str, path: &str) -> Option<String> {
-    let mut candidates = Vec::new();
-    let p = path.trim();
-    if p.is_empty() {
-        candidates.push(format!("https://docs.rs/{}/{}/", crate_name, version));
-        candidates.push(format!("https://docs.rs/crate/{}/{}/", crate_name, version));
-    } else {
-        candidates.push(format!("https://docs.rs/{}/{}/{}", crate_name, version, p));
-        candidates.push(format!("https://docs.rs/crate/{}/{}/{}", crate_name, version, p));
-        candidates.push(format!("https://docs.rs/{}/{}/{}", crate_name, version, p.trim_start_matches('/')));
+/// Check a registry API base ("https://host/api/v1") and strip any trailing slash.
+pub(crate) fn parse_registry_base(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    let url = reqwest::Url::parse(trimmed).map_err(|e| format!("invalid registry_base '{}': {}", raw, e))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(format!("invalid registry_base '{}': expected an http(s) URL like https://crates.io/api/v1", raw));
     }
-    for url in candidates {
-        if let Ok(Ok(resp)) = timeout(Duration::from_secs(12), client.get(&url).send()).await {
-            if resp.status().is_success() {
-                if let Ok(text) = resp.text().await {
-                    return Some(text);
-                }
-            }
-        }
+    Ok(trimmed.to_string())
+}
+
+/// Check a docs host base ("https://docs.rs") and strip any trailing slash.
+pub(crate) fn parse_docs_base(raw: &str) -> Result<String, String> {
+    let trimmed = raw.trim().trim_end_matches('/');
+    let url = reqwest::Url::parse(trimmed).map_err(|e| format!("invalid docs_base '{}': {}", raw, e))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(format!("invalid docs_base '{}': expected an http(s) URL like https://docs.rs", raw));
     }
-    None
+    Ok(trimmed.to_string())
 }
 
-async fn crawl_docs_rs_collect(
+/// Umbrella features worth suggesting when a crate ships with nothing enabled by default.
+const UMBRELLA_FEATURES: &[&str] = &["full", "all", "all-features", "everything"];
+
+/// What crates.io knows about one published version beyond its number.
+#[derive(Debug, Default)]
+struct VersionDetails {
+    /// `name -> enabled features`
+    features: HashMap<String, Vec<String>>,
+    /// RFC 3339 publish time.
+    created_at: Option<String>,
+    /// `package.edition` of the published manifest, when the registry reports it.
+    edition: Option<String>,
+}
+
+/// Feature table and publish time of one published version.
+async fn fetch_version_details(
     client: &Client,
+    registry_base: &str,
     crate_name: &str,
     version: &str,
-    max_pages: usize,
-) -> (Option<String>, usize, Vec<String>) {
-    let mut collected_html = Vec::new();
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
+) -> Result<VersionDetails, RegistryError> {
+    let url = format!("{}/crates/{}/{}", registry_base, crate_name, version);
+    let what = format!("crates.io metadata for {} {}", crate_name, version);
+    let resp = registry_get(client, &url, &what).await?;
+    if !resp.status().is_success() {
+        return Err(format!("registry returned {} for {}", resp.status(), what).into());
+    }
+    let v: serde_json::Value = resp.json().await.map_err(|e| format!("invalid JSON from {}: {}", what, e))?;
+    let ver = v.get("version").ok_or_else(|| format!("unexpected registry shape for {}", what))?;
+    let features = ver
+        .get("features")
+        .and_then(|f| f.as_object())
+        .map(|features| {
+            features
+                .iter()
+                .map(|(k, v)| {
+                    let enabled = v
+                        .as_array()
+                        .map(|a| a.iter().filter_map(|x| x.as_str().map(|s| s.to_string())).collect())
+                        .unwrap_or_default();
+                    (k.clone(), enabled)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(VersionDetails {
+        features,
+        created_at: ver.get("created_at").and_then(|c| c.as_str()).map(|s| s.to_string()),
+        edition: ver.get("edition").and_then(|e| e.as_str()).map(|s| s.to_string()),
+    })
+}
 
-    queue.push_back("".to_string());
-    queue.push_back(format!("{}/", crate_name));
+/// Known Rust editions, oldest first.
+const EDITIONS: &[&str] = &["2015", "2018", "2021", "2024"];
 
-    while let Some(path) = queue.pop_front() {
-        if visited.contains(&path) {
-            continue;
-        }
-        if collected_html.len() >= max_pages {
-            break;
-        }
-        if let Some(html) = fetch_docs_page(client, crate_name, version, &path).await {
-            collected_html.push(html.clone());
-            visited.insert(path.clone());
+/// Warning text when `crate_edition` is newer than the user's `edition`; `None` if
+/// it is compatible or either edition is unknown.
+fn edition_incompatibility(crate_edition: &str, edition: &str) -> Option<String> {
+    let rank = |e: &str| EDITIONS.iter().position(|x| *x == e.trim());
+    if rank(crate_edition)? <= rank(edition)? {
+        return None;
+    }
+    Some(format!(
+        "this version uses edition {}, newer than your edition {}; pick an older release or upgrade your project's edition",
+        crate_edition, edition
+    ))
+}
 
-            let doc = Html::parse_document(&html);
-            if let Ok(sel) = Selector::parse("a") {
-                for a in doc.select(&sel) {
-                    if let Some(href) = a.value().attr("href") {
-                        let nh = normalize_docs_href(href);
-                        if nh.is_empty() {
+/// Whole days between an RFC 3339 timestamp and now.
+fn days_since(timestamp: &str) -> Option<i64> {
+    let t = chrono::DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some((chrono::Utc::now() - t.with_timezone(&chrono::Utc)).num_days().max(0))
+}
+
+/// Versions older than this get a maintenance advisory in `warnings`.
+const STALE_VERSION_DAYS: i64 = 730;
+
+/// A yank this recent in the selected version's series gets a warning.
+const RECENT_YANK_DAYS: i64 = 90;
+
+/// Whether `a` and `b` are in the same Cargo-compatible series (same major, or same
+/// minor for 0.x, or same patch for 0.0.x).
+fn same_compat_series(a: &str, b: &str) -> bool {
+    let (Ok(a), Ok(b)) = (semver::Version::parse(a), semver::Version::parse(b)) else {
+        return false;
+    };
+    a.major == b.major && (a.major > 0 || (a.minor == b.minor && (a.minor > 0 || a.patch == b.patch)))
+}
+
+/// `name = { version = "x", features = ["full"] }` when the default feature set is
+/// empty but an umbrella feature exists; `None` otherwise.
+fn recommended_dependency_line(crate_name: &str, version: &str, features: &HashMap<String, Vec<String>>) -> Option<String> {
+    let default_empty = features.get("default").map(|d| d.is_empty()).unwrap_or(true);
+    if !default_empty {
+        return None;
+    }
+    let umbrella = UMBRELLA_FEATURES.iter().find(|f| features.contains_key(**f))?;
+    Some(format!(r#"{} = {{ version = "{}", features = ["{}"] }}"#, crate_name, version, umbrella))
+}
+
+// -------------------- helpers: robots.txt --------------------------------------
+
+/// Agent token we match robots.txt groups against.
+const ROBOTS_AGENT: &str = "mcp-query-rustdocs";
+
+/// Parsed robots.txt rules per origin, kept for the life of the process.
+static ROBOTS_CACHE: OnceLock<Mutex<HashMap<String, Arc<RobotsRules>>>> = OnceLock::new();
+
+/// Allow/Disallow rules from the robots.txt group that applies to us.
+#[derive(Debug, Default)]
+struct RobotsRules {
+    /// (allow, pattern)
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Keep the rules of groups naming our agent, or of the `*` group if none do.
+    fn parse(body: &str, agent: &str) -> Self {
+        let agent = agent.to_lowercase();
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // an empty Disallow means "allow everything"
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (key == "allow", value.to_string());
+                    if group_agents.iter().any(|a| a != "*" && agent.contains(a.as_str())) {
+                        specific.push(rule);
+                    } else if group_agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+        RobotsRules { rules: if specific.is_empty() { wildcard } else { specific } }
+    }
+
+    /// Longest matching rule wins; Allow wins ties; no match means allowed.
+    fn is_allowed(&self, path: &str) -> bool {
+        let mut best: Option<(usize, bool)> = None;
+        for (allow, pattern) in &self.rules {
+            if robots_pattern_matches(pattern, path) {
+                let len = pattern.len();
+                match best {
+                    Some((l, a)) if l > len || (l == len && a) => {}
+                    _ => best = Some((len, *allow)),
+                }
+            }
+        }
+        best.map(|(_, allow)| allow).unwrap_or(true)
+    }
+}
+
+/// robots.txt path matching with `*` wildcards and a trailing `$` anchor.
+fn robots_pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(p) => (p, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or("");
+    if !path.starts_with(first) {
+        return false;
+    }
+    let mut pos = first.len();
+    let rest: Vec<&str> = parts.collect();
+    for (i, part) in rest.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        if anchored && i == rest.len() - 1 {
+            return path[pos..].ends_with(part);
+        }
+        match path[pos..].find(part) {
+            Some(idx) => pos += idx + part.len(),
+            None => return false,
+        }
+    }
+    !anchored || pos == path.len() || rest.last().is_some_and(|p| p.is_empty())
+}
+
+/// Check `url` against its host's robots.txt, fetching and caching the rules on first use.
+/// Unreachable or missing robots.txt means everything is allowed.
+async fn robots_allows(client: &Client, url: &str) -> bool {
+    let Ok(parsed) = reqwest::Url::parse(url) else {
+        return true;
+    };
+    let Some(host) = parsed.host_str() else {
+        return true;
+    };
+    let origin = format!("{}://{}", parsed.scheme(), host);
+    let cache = ROBOTS_CACHE.get_or_init(Default::default);
+
+    let cached = cache.lock().unwrap_or_else(|e| e.into_inner()).get(&origin).cloned();
+    let rules = match cached {
+        Some(r) => r,
+        None => {
+            let robots_url = format!("{}/robots.txt", origin);
+            let mut rules = RobotsRules::default();
+            crate::rate_limit::throttle(&robots_url).await;
+            if let Ok(Ok(resp)) = timeout(Duration::from_secs(8), client.get(&robots_url).send()).await {
+                if resp.status().is_success() {
+                    if let Ok(body) = resp.text().await {
+                        rules = RobotsRules::parse(&body, ROBOTS_AGENT);
+                    }
+                }
+            }
+            let rules = Arc::new(rules);
+            cache
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(origin, rules.clone());
+            rules
+        }
+    };
+
+    let mut path = parsed.path().to_string();
+    if let Some(q) = parsed.query() {
+        path.push('?');
+        path.push_str(q);
+    }
+    rules.is_allowed(&path)
+}
+
+// -------------------- helpers: docs.rs crawling --------------------------------
+
+/// Resolve `href` against the page it appeared on and return it relative to the
+/// crawl `base`, or `None` if it points outside the crate's docs.
+fn normalize_docs_href(page_url: &str, href: &str, base: &str) -> Option<String> {
+    let mut resolved = reqwest::Url::parse(page_url).ok()?.join(href).ok()?;
+    resolved.set_fragment(None);
+    resolved.set_query(None);
+    let rel = resolved.as_str().strip_prefix(base)?;
+    Some(rel.trim_start_matches('/').to_string())
+}
+
+/// "https://docs.rs/{name}/{version}/" from a page URL docs.rs redirected us to,
+/// so renamed crates and lib paths that differ from the crate name still crawl.
+pub(crate) fn docs_base_from_url(final_url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(final_url).ok()?;
+    let mut segs = url.path_segments()?;
+    let name = segs.next().filter(|s| !s.is_empty() && *s != "crate")?;
+    let version = segs.next().filter(|s| !s.is_empty())?;
+    Some(format!("{}://{}/{}/{}/", url.scheme(), url.host_str()?, name, version))
+}
+
+/// Client for docs.rs pages with reqwest's transparent decoding turned off, so
+/// `fetch_compressed_text` can see the on-the-wire size before decoding itself.
+static DOCS_CLIENT: OnceLock<Client> = OnceLock::new();
+
+fn docs_client() -> &'static Client {
+    DOCS_CLIENT.get_or_init(|| {
+        crate::config::config()
+            .apply_tls(Client::builder())
+            .user_agent(crate::state::USER_AGENT)
+            .timeout(Duration::from_secs(18))
+            .no_gzip()
+            .no_brotli()
+            .no_deflate()
+            .build()
+            .unwrap_or_else(|_| Client::new())
+    })
+}
+
+/// When the next request to each host may go out, shared by every crawl in the process.
+static HOST_NEXT_SLOT: OnceLock<Mutex<HashMap<String, Instant>>> = OnceLock::new();
+
+/// Sleep until `url`'s host is due, reserving the slot after it so concurrent crawls
+/// queue up `delay` apart instead of all firing at once.
+async fn wait_for_host_slot(url: &str, delay: Duration) {
+    if delay.is_zero() {
+        return;
+    }
+    let Some(host) = reqwest::Url::parse(url).ok().and_then(|u| u.host_str().map(|h| h.to_string())) else {
+        return;
+    };
+    let start = {
+        let mut slots = HOST_NEXT_SLOT
+            .get_or_init(|| Mutex::new(HashMap::new()))
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        let now = Instant::now();
+        let start = slots.get(&host).copied().filter(|t| *t > now).unwrap_or(now);
+        slots.insert(host, start + delay);
+        start
+    };
+    tokio::time::sleep_until(start.into()).await;
+}
+
+/// Largest response body read from docs.rs or GitHub; longer bodies are cut off here.
+const MAX_BODY_BYTES: usize = 8 * 1024 * 1024;
+
+/// Read at most `max` bytes of a response body, stopping the download there.
+async fn read_body_capped(mut resp: reqwest::Response, max: usize) -> Option<Vec<u8>> {
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await.ok()? {
+        let room = max - body.len();
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            tracing::debug!(url = %resp.url(), max, "response body truncated at size cap");
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Some(body)
+}
+
+/// GET `url` asking for br/gzip, decode by hand and log compressed vs decoded size.
+/// Returns the final URL after redirects along with the body.
+pub(crate) async fn fetch_compressed_text(url: &str, delay: Duration) -> Option<(String, String)> {
+    fetch_compressed_text_logged(url, delay).await.1
+}
+
+/// `fetch_compressed_text`, also describing the request for `fetched_pages`.
+async fn fetch_compressed_text_logged(url: &str, delay: Duration) -> (FetchedPage, Option<(String, String)>) {
+    let mut record = FetchedPage {
+        requested_url: url.to_string(),
+        url: url.to_string(),
+        status: None,
+        bytes: 0,
+        error: None,
+    };
+    wait_for_host_slot(url, delay).await;
+    crate::rate_limit::throttle(url).await;
+    let req = docs_client()
+        .get(url)
+        .header(reqwest::header::ACCEPT_ENCODING, "br, gzip");
+    let resp = match timeout(Duration::from_secs(12), req.send()).await {
+        Ok(Ok(resp)) => resp,
+        Ok(Err(e)) => {
+            record.error = Some(format!("network error: {}", e));
+            return (record, None);
+        }
+        Err(_) => {
+            record.error = Some("timeout".to_string());
+            return (record, None);
+        }
+    };
+    record.url = resp.url().to_string();
+    record.status = Some(resp.status().as_u16());
+    if !resp.status().is_success() {
+        return (record, None);
+    }
+    let final_url = resp.url().to_string();
+    let encoding = resp
+        .headers()
+        .get(reqwest::header::CONTENT_ENCODING)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("identity")
+        .to_ascii_lowercase();
+    let Some(raw) = read_body_capped(resp, MAX_BODY_BYTES).await else {
+        record.error = Some(format!("body unreadable or over {} bytes", MAX_BODY_BYTES));
+        return (record, None);
+    };
+    let mut decoded = Vec::new();
+    let ok = match encoding.as_str() {
+        "br" => brotli::Decompressor::new(&raw[..], 4096)
+            .take(MAX_BODY_BYTES as u64)
+            .read_to_end(&mut decoded)
+            .is_ok(),
+        "gzip" | "x-gzip" => flate2::read::GzDecoder::new(&raw[..])
+            .take(MAX_BODY_BYTES as u64)
+            .read_to_end(&mut decoded)
+            .is_ok(),
+        _ => {
+            decoded = raw.to_vec();
+            true
+        }
+    };
+    if !ok {
+        tracing::debug!(url, encoding = %encoding, "failed to decode docs page body");
+        record.error = Some(format!("could not decode {} body", encoding));
+        return (record, None);
+    }
+    record.bytes = decoded.len();
+    tracing::debug!(
+        url,
+        encoding = %encoding,
+        compressed_bytes = raw.len(),
+        decompressed_bytes = decoded.len(),
+        "fetched docs page"
+    );
+    if final_url != url {
+        tracing::debug!(url, final_url = %final_url, "docs page redirected");
+    }
+    (record, Some((final_url, String::from_utf8_lossy(&decoded).into_owned())))
+}
+
+/// Fetch a docs page for a crate version from `docs_base` (docs.rs or a mirror with
+/// the same URL layout); returns (final URL, HTML).
+pub(crate) async fn fetch_docs_page(docs_base: &str, crate_name: &str, version: &str, path: &str, delay: Duration) -> Option<(String, String)> {
+    fetch_docs_page_logged(docs_base, crate_name, version, path, delay, &mut Vec::new()).await
+}
+
+/// `fetch_docs_page`, appending every candidate URL tried to `log`.
+async fn fetch_docs_page_logged(
+    docs_base: &str,
+    crate_name: &str,
+    version: &str,
+    path: &str,
+    delay: Duration,
+    log: &mut Vec<FetchedPage>,
+) -> Option<(String, String)> {
+    let mut candidates = Vec::new();
+    let p = path.trim();
+    if p.is_empty() {
+        candidates.push(format!("{}/{}/{}/", docs_base, crate_name, version));
+        candidates.push(format!("{}/crate/{}/{}/", docs_base, crate_name, version));
+    } else {
+        candidates.push(format!("{}/{}/{}/{}", docs_base, crate_name, version, p));
+        candidates.push(format!("{}/crate/{}/{}/{}", docs_base, crate_name, version, p));
+        candidates.push(format!("{}/{}/{}/{}", docs_base, crate_name, version, p.trim_start_matches('/')));
+    }
+    for url in candidates {
+        let (record, page) = fetch_compressed_text_logged(&url, delay).await;
+        log.push(record);
+        if page.is_some() {
+            return page;
+        }
+    }
+    None
+}
+
+/// "foo-bar" <-> "foo_bar"; `None` when the name has neither.
+fn alternate_crate_name(crate_name: &str) -> Option<String> {
+    if crate_name.contains('-') {
+        Some(crate_name.replace('-', "_"))
+    } else if crate_name.contains('_') {
+        Some(crate_name.replace('_', "-"))
+    } else {
+        None
+    }
+}
+
+async fn crawl_docs_rs_collect(
+    client: &Client,
+    crate_name: &str,
+    version: &str,
+    opts: &EnrichOptions,
+) -> CrawlOutcome {
+    let max_pages = opts.docs_max_pages;
+    let mut budget_exhausted = false;
+    // switches to the other hyphen/underscore spelling if the root 404s under the given one
+    let mut docs_name = crate_name.to_string();
+    let mut default_base = format!("{}/{}/{}/", opts.docs_base, crate_name, version);
+    let mut base = default_base.clone();
+    let mut collected_html = Vec::new();
+    let mut visited = HashSet::new();
+    let mut fetched_log = Vec::new();
+    // (path, link depth from the crate root)
+    let mut queue: VecDeque<(String, usize)> = VecDeque::new();
+
+    let resume = opts
+        .resume
+        .as_ref()
+        .filter(|c| c.crate_name == crate_name && c.version == version);
+    if let Some(cursor) = resume {
+        base = cursor.base.clone();
+        visited.extend(cursor.visited.iter().cloned());
+        queue.extend(cursor.queue.iter().cloned());
+    } else if opts.shallow {
+        // the lib's root module page, falling back to whatever docs.rs redirects "" to
+        queue.push_back((format!("{}/", crate_name.replace('-', "_")), 0usize));
+        queue.push_back(("".to_string(), 0));
+    } else {
+        queue.push_back(("".to_string(), 0usize));
+        queue.push_back((format!("{}/", crate_name), 0));
+    }
+
+    while let Some((path, depth)) = queue.pop_front() {
+        if visited.contains(&path) {
+            continue;
+        }
+        if collected_html.len() >= max_pages {
+            queue.push_front((path, depth));
+            break;
+        }
+        if opts.respect_robots {
+            let url = format!("{}{}", base, path.trim_start_matches('/'));
+            if !robots_allows(client, &url).await {
+                fetched_log.push(FetchedPage {
+                    requested_url: url.clone(),
+                    url,
+                    status: None,
+                    bytes: 0,
+                    error: Some("disallowed by robots.txt".to_string()),
+                });
+                visited.insert(path);
+                continue;
+            }
+        }
+        if let Some(ref budget) = opts.page_budget {
+            if budget.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1)).is_err() {
+                budget_exhausted = true;
+                queue.push_front((path, depth));
+                break;
+            }
+        }
+        let fetched = if base == default_base {
+            fetch_docs_page_logged(&opts.docs_base, &docs_name, version, &path, opts.crawl_delay, &mut fetched_log).await
+        } else {
+            let (record, page) =
+                fetch_compressed_text_logged(&format!("{}{}", base, path.trim_start_matches('/')), opts.crawl_delay).await;
+            fetched_log.push(record);
+            page
+        };
+        if let Some((final_url, html)) = fetched {
+            // the first page tells us where docs.rs really keeps this crate's docs
+            if collected_html.is_empty() && resume.is_none() {
+                if let Some(b) = docs_base_from_url(&final_url) {
+                    if b != base {
+                        tracing::debug!(crate_name, from = %base, to = %b, "docs.rs base redirected");
+                        base = b;
+                    }
+                }
+            }
+            collected_html.push(html.clone());
+            visited.insert(path.clone());
+            if opts.shallow {
+                queue.clear();
+                break;
+            }
+            if depth >= opts.docs_max_depth {
+                continue;
+            }
+
+            let doc = Html::parse_document(&html);
+            if let Ok(sel) = Selector::parse("a") {
+                for a in doc.select(&sel) {
+                    if let Some(href) = a.value().attr("href") {
+                        let Some(nh) = normalize_docs_href(&final_url, href, &base) else {
+                            continue;
+                        };
+                        if nh.is_empty() {
                             continue;
                         }
                         // heuristics: only follow links containing crate_name or starting with "crate" or that look like module pages
                         if nh.contains(crate_name) || nh.starts_with("crate") || nh.contains("struct") || nh.contains("fn") || nh.contains("module") || nh.ends_with(".html") {
-                            if !visited.contains(&nh) && !queue.contains(&nh) {
-                                queue.push_back(nh);
+                            if !visited.contains(&nh) && !queue.iter().any(|(p, _)| p == &nh) {
+                                queue.push_back((nh, depth + 1));
                             }
                         }
                     }
                 }
             }
         } else {
+            let root_missing = path.is_empty()
+                && collected_html.is_empty()
+                && resume.is_none()
+                && docs_name == crate_name
+                && fetched_log.iter().any(|r| r.status == Some(404));
+            if let Some(alt) = alternate_crate_name(crate_name).filter(|_| root_missing) {
+                tracing::debug!(crate_name, alt = %alt, "docs root not found, retrying with the alternate name");
+                default_base = format!("{}/{}/{}/", opts.docs_base, alt, version);
+                base = default_base.clone();
+                docs_name = alt;
+                queue.push_front((path, depth));
+                continue;
+            }
             visited.insert(path);
         }
     }
 
+    let docs_name = (docs_name != crate_name).then_some(docs_name);
     if collected_html.is_empty() {
-        (None, 0, Vec::new())
+        CrawlOutcome { budget_exhausted, fetched: fetched_log, docs_name, ..Default::default() }
+    } else {
+        // HashSet order changes run to run; keep the cursor byte-identical for the same crawl
+        let mut visited: Vec<String> = visited.into_iter().collect();
+        visited.sort();
+        CrawlOutcome {
+            pages: collected_html,
+            visited,
+            budget_exhausted,
+            base: Some(base),
+            remaining: queue.into_iter().collect(),
+            fetched: fetched_log,
+            docs_name,
+        }
+    }
+}
+
+// -------------------- helpers: rustdoc JSON ----------------------------------
+
+/// Structured docs pulled from a rustdoc JSON build.
+#[derive(Debug, Default)]
+struct RustdocJsonData {
+    symbols: Vec<String>,
+    function_signatures: Vec<String>,
+    item_names: Vec<String>,
+    docs_text: String,
+    /// Full docs of the root module and every public item that has any.
+    item_docs: Vec<ItemDoc>,
+    code_snippets: Vec<String>,
+}
+
+/// Download docs.rs's gzipped rustdoc JSON for a crate version.
+pub(crate) async fn fetch_rustdoc_json(client: &Client, docs_base: &str, crate_name: &str, version: &str, verbose_errors: bool) -> Result<serde_json::Value, String> {
+    let url = format!("{}/crate/{}/{}/json.gz", docs_base, crate_name, version);
+    crate::rate_limit::throttle(&url).await;
+    let resp = timeout(Duration::from_secs(20), client.get(&url).send())
+        .await
+        .map_err(|_| format!("timeout fetching rustdoc JSON for '{}'", crate_name))?
+        .map_err(|e| format!("network error fetching rustdoc JSON for '{}': {}", crate_name, e))?;
+    if !resp.status().is_success() {
+        let status = describe_error_response(resp, verbose_errors).await;
+        return Err(format!("docs.rs returned {} for rustdoc JSON of '{}' {}", status, crate_name, version));
+    }
+    let bytes = resp
+        .bytes()
+        .await
+        .map_err(|e| format!("failed reading rustdoc JSON for '{}': {}", crate_name, e))?;
+    let mut raw = Vec::new();
+    flate2::read::GzDecoder::new(&bytes[..])
+        .read_to_end(&mut raw)
+        .map_err(|e| format!("failed to decompress rustdoc JSON for '{}': {}", crate_name, e))?;
+    serde_json::from_slice(&raw).map_err(|e| format!("invalid rustdoc JSON for '{}': {}", crate_name, e))
+}
+
+/// Read a bool that rustdoc JSON has spelled both `is_x` and `x` across format versions.
+fn json_flag(v: &serde_json::Value, new_key: &str, old_key: &str) -> bool {
+    v.get(new_key).or_else(|| v.get(old_key)).and_then(|b| b.as_bool()).unwrap_or(false)
+}
+
+/// Best-effort rendering of a rustdoc JSON `Type` back into Rust syntax.
+fn render_rustdoc_type(t: &serde_json::Value) -> String {
+    if let Some(s) = t.as_str() {
+        return if s == "infer" { "_".into() } else { s.to_string() };
+    }
+    let Some((kind, inner)) = t.as_object().and_then(|o| o.iter().next()) else {
+        return "_".into();
+    };
+    match kind.as_str() {
+        "primitive" | "generic" => inner.as_str().unwrap_or("_").to_string(),
+        "resolved_path" => render_rustdoc_path(inner),
+        "borrowed_ref" => {
+            let lt = inner.get("lifetime").and_then(|l| l.as_str()).map(|l| format!("{} ", l)).unwrap_or_default();
+            let m = if json_flag(inner, "is_mutable", "mutable") { "mut " } else { "" };
+            format!("&{}{}{}", lt, m, inner.get("type").map(render_rustdoc_type).unwrap_or_default())
+        }
+        "raw_pointer" => {
+            let m = if json_flag(inner, "is_mutable", "mutable") { "mut" } else { "const" };
+            format!("*{} {}", m, inner.get("type").map(render_rustdoc_type).unwrap_or_default())
+        }
+        "slice" => format!("[{}]", render_rustdoc_type(inner)),
+        "array" => format!(
+            "[{}; {}]",
+            inner.get("type").map(render_rustdoc_type).unwrap_or_default(),
+            inner.get("len").and_then(|l| l.as_str()).unwrap_or("_")
+        ),
+        "tuple" => {
+            let parts: Vec<String> = inner.as_array().map(|a| a.iter().map(render_rustdoc_type).collect()).unwrap_or_default();
+            format!("({})", parts.join(", "))
+        }
+        "impl_trait" => format!("impl {}", render_rustdoc_bounds(inner)),
+        "dyn_trait" => {
+            let traits: Vec<String> = inner
+                .get("traits")
+                .and_then(|a| a.as_array())
+                .map(|a| a.iter().filter_map(|p| p.get("trait")).map(render_rustdoc_path).collect())
+                .unwrap_or_default();
+            format!("dyn {}", traits.join(" + "))
+        }
+        "qualified_path" => format!(
+            "<{} as {}>::{}",
+            inner.get("self_type").map(render_rustdoc_type).unwrap_or_default(),
+            inner.get("trait").map(render_rustdoc_path).unwrap_or_default(),
+            inner.get("name").and_then(|n| n.as_str()).unwrap_or("_")
+        ),
+        _ => "_".into(),
+    }
+}
+
+/// Render a rustdoc JSON path (`name`/`path` plus angle-bracketed generic args).
+fn render_rustdoc_path(p: &serde_json::Value) -> String {
+    let name = p
+        .get("path")
+        .or_else(|| p.get("name"))
+        .and_then(|n| n.as_str())
+        .unwrap_or("_")
+        .to_string();
+    let args: Vec<String> = p
+        .get("args")
+        .and_then(|a| a.get("angle_bracketed"))
+        .and_then(|a| a.get("args"))
+        .and_then(|a| a.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|g| {
+                    g.get("type")
+                        .map(render_rustdoc_type)
+                        .or_else(|| g.get("lifetime").and_then(|l| l.as_str()).map(|l| l.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    if args.is_empty() {
+        name
     } else {
-        let combined = collected_html.join("\n");
-        (Some(combined), collected_html.len(), visited.into_iter().collect())
+        format!("{}<{}>", name, args.join(", "))
+    }
+}
+
+fn render_rustdoc_bounds(bounds: &serde_json::Value) -> String {
+    let parts: Vec<String> = bounds
+        .as_array()
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|b| {
+                    b.get("trait_bound")
+                        .and_then(|tb| tb.get("trait"))
+                        .map(render_rustdoc_path)
+                        .or_else(|| b.get("outlives").and_then(|l| l.as_str()).map(|l| l.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    parts.join(" + ")
+}
+
+/// Render `fn name<G>(args) -> Out` from a rustdoc JSON function item.
+pub(crate) fn render_rustdoc_fn(name: &str, func: &serde_json::Value) -> String {
+    let sig = func.get("sig").or_else(|| func.get("decl")).cloned().unwrap_or_default();
+    let header = func.get("header").cloned().unwrap_or_default();
+    let mut prefix = String::new();
+    if json_flag(&header, "is_const", "const") {
+        prefix.push_str("const ");
+    }
+    if json_flag(&header, "is_async", "async") {
+        prefix.push_str("async ");
+    }
+    if json_flag(&header, "is_unsafe", "unsafe") {
+        prefix.push_str("unsafe ");
+    }
+    let generics: Vec<String> = func
+        .get("generics")
+        .and_then(|g| g.get("params"))
+        .and_then(|p| p.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|p| p.get("name").and_then(|n| n.as_str()))
+                // synthetic `impl Trait` params show up as generics; they're already in the args
+                .filter(|n| !n.starts_with("impl "))
+                .map(|n| n.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    let inputs: Vec<String> = sig
+        .get("inputs")
+        .and_then(|i| i.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|pair| {
+                    let pair = pair.as_array()?;
+                    let arg = pair.first()?.as_str()?;
+                    let ty = pair.get(1).map(render_rustdoc_type).unwrap_or_default();
+                    Some(if arg == "self" {
+                        match ty.as_str() {
+                            "Self" => "self".to_string(),
+                            "&Self" => "&self".to_string(),
+                            "&mut Self" => "&mut self".to_string(),
+                            _ => format!("self: {}", ty),
+                        }
+                    } else {
+                        format!("{}: {}", arg, ty)
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let output = match sig.get("output") {
+        Some(o) if !o.is_null() => format!(" -> {}", render_rustdoc_type(o)),
+        _ => String::new(),
+    };
+    let generics = if generics.is_empty() { String::new() } else { format!("<{}>", generics.join(", ")) };
+    format!("{}fn {}{}({}){}", prefix, name, generics, inputs.join(", "), output)
+}
+
+/// Pull ```-fenced code blocks out of markdown doc comments, skipping fences in
+/// languages other than Rust unless they are listed in `languages`.
+fn extract_fenced_code(md: &str, languages: &[String]) -> Vec<String> {
+    let mut out = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+    let mut is_rust = true;
+    for line in md.lines() {
+        let t = line.trim_start();
+        if t.starts_with("```") {
+            if let Some(lines) = current.take() {
+                if is_rust {
+                    let block = lines.join("\n").trim().to_string();
+                    if !block.is_empty() {
+                        out.push(block);
+                    }
+                }
+            } else {
+                let info = t.trim_start_matches('`').trim();
+                is_rust = info.is_empty()
+                    || info.split(',').any(|tag| matches!(tag.trim(), "rust" | "no_run" | "should_panic" | "ignore" | "edition2018" | "edition2021" | "edition2024"))
+                    || info.split(',').any(|tag| languages.contains(&normalize_snippet_language(tag)));
+                current = Some(Vec::new());
+            }
+        } else if let Some(ref mut lines) = current {
+            // rustdoc hides lines starting with "# " from rendered examples
+            if t != "#" && !t.starts_with("# ") {
+                lines.push(line);
+            }
+        }
+    }
+    out
+}
+
+/// Turn a parsed rustdoc JSON document into the fields `CrateResult` exposes.
+fn parse_rustdoc_json(v: &serde_json::Value, languages: &[String]) -> RustdocJsonData {
+    let mut data = RustdocJsonData::default();
+    let empty = serde_json::Map::new();
+    let index = v.get("index").and_then(|i| i.as_object()).unwrap_or(&empty);
+    let paths = v.get("paths").and_then(|p| p.as_object()).unwrap_or(&empty);
+
+    // public, nameable items of the local crate (crate_id 0)
+    for entry in paths.values() {
+        if entry.get("crate_id").and_then(|c| c.as_u64()) != Some(0) {
+            continue;
+        }
+        let kind = entry.get("kind").and_then(|k| k.as_str()).unwrap_or("item");
+        let path: Vec<&str> = entry
+            .get("path")
+            .and_then(|p| p.as_array())
+            .map(|a| a.iter().filter_map(|s| s.as_str()).collect())
+            .unwrap_or_default();
+        if !path.is_empty() {
+            data.symbols.push(format!("{} {}", kind, path.join("::")));
+        }
+    }
+    data.symbols.sort();
+    data.symbols.dedup();
+
+    let root_id = v.get("root").map(|r| r.to_string().trim_matches('"').to_string());
+    let mut root_docs = String::new();
+    let mut item_docs = Vec::new();
+
+    for (id, item) in index {
+        if item.get("crate_id").and_then(|c| c.as_u64()) != Some(0) {
+            continue;
+        }
+        let visible = matches!(item.get("visibility").and_then(|x| x.as_str()), Some("public") | Some("default"));
+        let name = item.get("name").and_then(|n| n.as_str());
+        let docs = item.get("docs").and_then(|d| d.as_str()).unwrap_or("");
+
+        if Some(id.as_str()) == root_id.as_deref() {
+            root_docs = docs.to_string();
+            if !docs.is_empty() {
+                data.item_docs.push(ItemDoc {
+                    path: name.unwrap_or_default().to_string(),
+                    doc_text: truncate_chars(docs.trim(), ITEM_DOC_MAX_CHARS),
+                });
+            }
+            data.code_snippets.extend(extract_fenced_code(docs, languages));
+            continue;
+        }
+        if !visible {
+            continue;
+        }
+        let Some(name) = name else { continue };
+
+        let display_path = paths
+            .get(id)
+            .and_then(|p| p.get("path"))
+            .and_then(|p| p.as_array())
+            .map(|a| a.iter().filter_map(|s| s.as_str()).collect::<Vec<_>>().join("::"))
+            .unwrap_or_else(|| name.to_string());
+
+        if let Some(func) = item.get("inner").and_then(|i| i.get("function")) {
+            data.function_signatures.push(render_rustdoc_fn(name, func));
+        }
+        data.item_names.push(display_path.clone());
+        if !docs.is_empty() {
+            // first paragraph only; the whole docs for every item would be enormous
+            let first_para = docs.split("\n\n").next().unwrap_or("").trim();
+            item_docs.push(format!("{}: {}", display_path, first_para));
+            data.item_docs.push(ItemDoc {
+                path: display_path.clone(),
+                doc_text: truncate_chars(docs.trim(), ITEM_DOC_MAX_CHARS),
+            });
+            data.code_snippets.extend(extract_fenced_code(docs, languages));
+        }
+    }
+
+    data.function_signatures.sort();
+    data.function_signatures.dedup();
+    data.item_names.sort();
+    data.item_names.dedup();
+    item_docs.sort();
+
+    let mut text = root_docs;
+    for d in item_docs {
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str(&d);
     }
+    data.docs_text = text;
+    data
 }
 
 // -------------------- helpers: extraction & cleaning --------------------------
@@ -388,6 +2146,138 @@ fn extract_anchor_items_from_html(html: &str, max_items: usize) -> Vec<String> {
     items.into_iter().take(max_items).collect()
 }
 
+/// CSS classes docs.rs puts on links to items, which double as the item kind.
+const DOC_ITEM_KINDS: &[&str] = &[
+    "struct", "enum", "fn", "trait", "macro", "mod", "type", "constant", "static", "union",
+    "attr", "derive", "primitive", "traitalias", "keyword",
+];
+
+/// Item links (`<a class="struct" href="struct.Foo.html">Foo</a>`) with their kind.
+fn extract_doc_items_from_html(html: &str, max_items: usize) -> Vec<DocItem> {
+    let mut items = Vec::new();
+    let doc = Html::parse_document(html);
+    let Ok(sel) = Selector::parse("a[href]") else {
+        return items;
+    };
+    let mut seen = HashSet::new();
+    for a in doc.select(&sel) {
+        if items.len() >= max_items {
+            break;
+        }
+        let Some(kind) = a.value().classes().find(|c| DOC_ITEM_KINDS.contains(c)) else {
+            continue;
+        };
+        let name = normalize_anchor_text(&a.text().collect::<String>());
+        if name.is_empty() {
+            continue;
+        }
+        let href = a.value().attr("href").unwrap_or_default();
+        let href = href.split('#').next().unwrap_or_default();
+        let mut path = href;
+        while let Some(rest) = path.strip_prefix("../").or_else(|| path.strip_prefix("./")) {
+            path = rest;
+        }
+        if seen.insert((kind.to_string(), name.clone())) {
+            items.push(DocItem { name, kind: kind.to_string(), path: path.to_string() });
+        }
+    }
+    items
+}
+
+/// The page's own item and its top doc block. rustdoc titles pages
+/// "Mutex in tokio::sync - Rust" (or "tokio - Rust" for the crate root).
+fn extract_item_doc_from_html(html: &str, max_chars: usize) -> Option<ItemDoc> {
+    let doc = Html::parse_document(html);
+    let title = doc
+        .select(&Selector::parse("title").ok()?)
+        .next()?
+        .text()
+        .collect::<String>();
+    let title = title.trim().trim_end_matches(" - Rust").trim();
+    let path = match title.split_once(" in ") {
+        Some((name, parent)) => format!("{}::{}", parent.trim(), name.trim()),
+        None => title.to_string(),
+    };
+    if path.is_empty() || path.contains(' ') {
+        return None;
+    }
+    // newer rustdoc wraps the top docs in a toggle; older output has a bare docblock
+    let text = ["details.top-doc > .docblock", "#main-content > .docblock"]
+        .iter()
+        .filter_map(|s| Selector::parse(s).ok())
+        .find_map(|sel| doc.select(&sel).next())?
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    Some(ItemDoc { path, doc_text: truncate_chars(&text, max_chars) })
+}
+
+/// Every item on a crate's `all.html` page. That page links items without kind
+/// classes, so the kind comes from the `kind.Name.html` file name instead.
+pub(crate) fn extract_all_items_from_html(html: &str) -> Vec<DocItem> {
+    let mut items = Vec::new();
+    let doc = Html::parse_document(html);
+    let Ok(sel) = Selector::parse("ul.all-items a[href]") else {
+        return items;
+    };
+    let mut seen = HashSet::new();
+    for a in doc.select(&sel) {
+        let href = a.value().attr("href").unwrap_or_default();
+        let file = href.rsplit('/').next().unwrap_or_default();
+        let Some((kind, name)) = file.strip_suffix(".html").and_then(|f| f.split_once('.')) else {
+            continue;
+        };
+        if !DOC_ITEM_KINDS.contains(&kind) || !seen.insert(href.to_string()) {
+            continue;
+        }
+        items.push(DocItem { name: name.to_string(), kind: kind.to_string(), path: href.to_string() });
+    }
+    items
+}
+
+/// The `::` path of an `all.html` item, rooted at `lib`:
+/// "sync/struct.Mutex.html" -> "tokio::sync::Mutex".
+pub(crate) fn all_items_path(lib: &str, item: &DocItem) -> String {
+    let mut segs: Vec<&str> = item.path.split('/').filter(|s| !s.is_empty()).collect();
+    segs.pop();
+    let mut path = lib.to_string();
+    for s in segs {
+        path.push_str("::");
+        path.push_str(s);
+    }
+    path.push_str("::");
+    path.push_str(&item.name);
+    path
+}
+
+/// `DocItem`s from rustdoc JSON "kind path" symbol strings.
+fn doc_items_from_symbols(symbols: &[String]) -> Vec<DocItem> {
+    symbols
+        .iter()
+        .filter_map(|s| {
+            let (kind, path) = s.split_once(' ')?;
+            let kind = match kind {
+                "function" => "fn",
+                "module" => "mod",
+                "type_alias" => "type",
+                "trait_alias" => "traitalias",
+                "proc_attribute" => "attr",
+                "proc_derive" => "derive",
+                other => other,
+            };
+            Some(DocItem {
+                name: path.rsplit("::").next().unwrap_or(path).to_string(),
+                kind: kind.to_string(),
+                path: path.to_string(),
+            })
+        })
+        .collect()
+}
+
 fn clean_code_snippet(snip: &str) -> Option<String> {
     let mut lines: Vec<&str> = snip.lines().collect();
     // remove leading lines that are pure numbers or copyright boilerplate lines often with line numbers
@@ -415,7 +2305,161 @@ fn clean_code_snippet(snip: &str) -> Option<String> {
     }
 }
 
-fn extract_code_blocks_from_html(html: &str, max_blocks: usize) -> Vec<String> {
+const RUST_KEYWORDS: &[&str] = &[
+    "fn", "let", "use", "pub", "impl", "struct", "enum", "match", "mod", "trait", "async", "await",
+    "mut", "where", "extern", "crate", "const", "static", "return", "self", "Self", "dyn", "unsafe",
+    "loop", "while", "for", "if", "else", "move", "ref", "type", "Some", "None", "Ok", "Err",
+];
+
+/// Blocks scoring below this are treated as prose or another language.
+const RUST_LIKENESS_THRESHOLD: f32 = 1.0;
+/// `code_only` asks for blocks that look like whole, compilable Rust.
+const RUST_CODE_ONLY_THRESHOLD: f32 = 2.0;
+
+/// Heuristic "how much does this look like Rust" score. Structure (`;` line ends,
+/// braces, `::` paths, `->`/`=>`, macro calls) and keyword density add to it;
+/// sentence-like prose without any of that subtracts.
+fn rust_likeness_score(text: &str) -> f32 {
+    let words: Vec<&str> = text
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.is_empty() {
+        return 0.0;
+    }
+    let keyword_hits = words.iter().filter(|w| RUST_KEYWORDS.contains(w)).count();
+    let density = keyword_hits as f32 / words.len() as f32;
+
+    let lines: Vec<&str> = text.lines().map(str::trim).filter(|l| !l.is_empty()).collect();
+    let semicolon_lines = lines.iter().filter(|l| l.ends_with(';')).count();
+    let has_braces = text.contains('{') && text.contains('}');
+    let has_macro_call = text.contains("!(") || text.contains("![") || text.contains("!{");
+
+    let mut score = (density * 4.0).min(1.5);
+    if semicolon_lines > 0 {
+        score += 1.0;
+    }
+    if has_braces {
+        score += 1.0;
+    }
+    if text.contains("::") {
+        score += 0.5;
+    }
+    if text.contains("->") || text.contains("=>") {
+        score += 0.5;
+    }
+    if has_macro_call {
+        score += 0.5;
+    }
+
+    // prose: sentences ending in '.', several words each, and no code punctuation at all
+    let sentence_lines = lines
+        .iter()
+        .filter(|l| l.ends_with('.') && l.split_whitespace().count() >= 6)
+        .count();
+    if semicolon_lines == 0 && !has_braces && sentence_lines * 2 >= lines.len() {
+        score -= 1.5;
+    }
+    score
+}
+
+/// How many chars after an opening `'` belong to a char literal (through its closing
+/// quote), or 0 when the quote starts a lifetime or label like `'a` or `'static`.
+fn char_literal_len(rest: &[char]) -> usize {
+    match rest {
+        // '\n', '\'', '\u{1F600}': skip the escaped char, then find the closing quote
+        ['\\', ..] => rest.iter().skip(2).take(10).position(|&c| c == '\'').map_or(0, |p| p + 3),
+        [_, '\'', ..] => 2,
+        _ => 0,
+    }
+}
+
+/// `()`, `[]` and `{}` all close, ignoring anything inside string/char literals.
+fn delimiters_balanced(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    let mut stack = Vec::new();
+    let mut in_str = false;
+    let mut escaped = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        i += 1;
+        if in_str {
+            match (escaped, c) {
+                (true, _) => escaped = false,
+                (false, '\\') => escaped = true,
+                (false, '"') => in_str = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_str = true,
+            '\'' => i += char_literal_len(&chars[i..]),
+            '(' | '[' | '{' => stack.push(c),
+            ')' | ']' | '}' => {
+                let open = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    _ => '{',
+                };
+                if stack.pop() != Some(open) {
+                    return false;
+                }
+            }
+            _ => {}
+        }
+    }
+    !in_str && stack.is_empty()
+}
+
+/// Keep a candidate block? `code_only` raises the bar to blocks that look complete
+/// enough to compile.
+fn is_rust_like(text: &str, code_only: bool) -> bool {
+    if code_only {
+        rust_likeness_score(text) >= RUST_CODE_ONLY_THRESHOLD && delimiters_balanced(text)
+    } else {
+        rust_likeness_score(text) >= RUST_LIKENESS_THRESHOLD
+    }
+}
+
+/// Canonical name for a code block language tag ("rs" -> "rust", "bash" -> "sh", ...).
+fn normalize_snippet_language(tag: &str) -> String {
+    let tag = tag.trim().to_lowercase();
+    let tag = tag.strip_prefix("language-").unwrap_or(&tag);
+    match tag {
+        "rs" | "rust-example-rendered" => "rust".to_string(),
+        "bash" | "shell" | "console" | "shell-session" | "zsh" => "sh".to_string(),
+        "yml" => "yaml".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// Language a rendered code block is tagged with, from its own classes or those of
+/// a `<code>` child / `<pre>` parent. docs.rs renders Rust as `pre.rust` and other
+/// fences as `language-xxx`.
+pub(crate) fn code_block_language(el: &scraper::ElementRef) -> Option<String> {
+    let mut classes: Vec<&str> = el.value().classes().collect();
+    for child in el.children().filter_map(scraper::ElementRef::wrap) {
+        if child.value().name() == "code" {
+            classes.extend(child.value().classes());
+        }
+    }
+    if let Some(parent) = el.parent().and_then(scraper::ElementRef::wrap) {
+        if parent.value().name() == "pre" {
+            classes.extend(parent.value().classes());
+        }
+    }
+    if classes.iter().any(|c| *c == "rust" || *c == "language-rust") {
+        return Some("rust".to_string());
+    }
+    classes
+        .iter()
+        .find(|c| c.starts_with("language-"))
+        .map(|c| normalize_snippet_language(c))
+}
+
+fn extract_code_blocks_from_html(html: &str, max_blocks: usize, code_only: bool, languages: &[String]) -> Vec<String> {
     let mut blocks = Vec::new();
     let doc = Html::parse_document(html);
     if let Ok(sel) = Selector::parse("pre, code, div.example, div.rust") {
@@ -423,14 +2467,33 @@ fn extract_code_blocks_from_html(html: &str, max_blocks: usize) -> Vec<String> {
             if blocks.len() >= max_blocks {
                 break;
             }
+            if code_only {
+                // inline `code` spans are fragments, and rustdoc marks examples that won't build
+                let classes: Vec<&str> = el.value().classes().collect();
+                if el.value().name() == "code" && !el.parent().and_then(|p| p.value().as_element()).is_some_and(|p| p.name() == "pre") {
+                    continue;
+                }
+                if classes.iter().any(|c| *c == "ignore" || *c == "compile_fail") {
+                    continue;
+                }
+            }
             let text = el.text().collect::<Vec<_>>().join("\n");
             let trimmed = text.trim();
             if trimmed.is_empty() {
                 continue;
             }
-            // crude rust-likeness check
-            if !(trimmed.contains("fn ") || trimmed.contains("use ") || trimmed.contains("let ") || trimmed.contains("extern crate") || trimmed.contains("cargo") || trimmed.contains("pub fn")) {
-                continue;
+            // tagged non-Rust blocks (toml, sh, ...) only when asked for, and without the Rust checks
+            match code_block_language(&el) {
+                Some(lang) if lang != "rust" => {
+                    if !languages.contains(&lang) {
+                        continue;
+                    }
+                }
+                _ => {
+                    if !is_rust_like(trimmed, code_only) {
+                        continue;
+                    }
+                }
             }
             if let Some(clean) = clean_code_snippet(trimmed) {
                 blocks.push(clean);
@@ -440,26 +2503,127 @@ fn extract_code_blocks_from_html(html: &str, max_blocks: usize) -> Vec<String> {
     blocks
 }
 
-fn extract_text_aggregate(html: &str) -> String {
+/// Candidate docs.rs paths (relative to the version root) for an item path, one per kind.
+/// "sync::Mutex" with ["struct", "enum"] -> ["tokio/sync/struct.Mutex.html", "tokio/sync/enum.Mutex.html"]
+pub(crate) fn item_path_candidates(crate_name: &str, item_path: &str, kinds: &[&str]) -> Vec<String> {
+    let item_path = item_path.trim().trim_start_matches('/');
+    if item_path.ends_with(".html") {
+        return vec![item_path.to_string()];
+    }
+    let lib_name = crate_name.replace('-', "_");
+    let mut segments: Vec<&str> = item_path.split("::").filter(|s| !s.is_empty()).collect();
+    if segments.first().is_some_and(|s| *s == lib_name || *s == "crate") {
+        segments.remove(0);
+    }
+    let Some(name) = segments.pop() else {
+        return Vec::new();
+    };
+    let mut dir = lib_name.clone();
+    for m in &segments {
+        dir.push('/');
+        dir.push_str(m);
+    }
+    kinds
+        .iter()
+        .map(|kind| format!("{}/{}.{}.html", dir, kind, name))
+        .collect()
+}
+
+/// Pull the "Trait Implementations" headers out of a struct/enum page.
+fn extract_trait_impls_from_html(html: &str) -> Vec<String> {
+    let mut out = Vec::new();
     let doc = Html::parse_document(html);
-    let selectors = ["main", "div.content", "div#main", "article", "body"];
-    for s in &selectors {
-        if let Ok(sel) = Selector::parse(s) {
-            if let Some(node) = doc.select(&sel).next() {
-                let text = node.text().collect::<Vec<_>>().join(" ");
-                let cleaned = text.split_whitespace().collect::<Vec<_>>().join(" ");
-                if !cleaned.is_empty() {
-                    return cleaned;
+    if let Ok(sel) = Selector::parse("#trait-implementations-list .impl > .code-header") {
+        for el in doc.select(&sel) {
+            let text = normalize_anchor_text(&el.text().collect::<Vec<_>>().join(" "));
+            if !text.is_empty() && !out.contains(&text) {
+                out.push(text);
+            }
+        }
+    }
+    out
+}
+
+/// First `max` chars of `s`, with an ellipsis when something was cut.
+fn truncate_chars(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &s[..idx]),
+        None => s.to_string(),
+    }
+}
+
+/// Condense per-page docs text into roughly `budget` chars. The crate root page
+/// gets up to half, its first example up to a quarter, and the other pages split
+/// whatever is left evenly.
+fn summarize_docs_pages(pages: &[String], budget: usize) -> String {
+    let Some(root) = pages.first() else {
+        return String::new();
+    };
+    let mut parts = Vec::new();
+    let mut used = 0usize;
+
+    let root_text = truncate_chars(&extract_text_aggregate(root), budget / 2);
+    used += root_text.chars().count();
+    parts.push(root_text);
+
+    if let Some(example) = extract_code_blocks_from_html(root, 1, false, &[]).into_iter().next() {
+        let example = truncate_chars(&example, budget / 4);
+        used += example.chars().count();
+        parts.push(format!("Example:\n{}", example));
+    }
+
+    let rest = &pages[1..];
+    if !rest.is_empty() && used < budget {
+        let share = (budget - used) / rest.len();
+        if share > 0 {
+            for page in rest {
+                let text = truncate_chars(&extract_text_aggregate(page), share);
+                if !text.is_empty() {
+                    parts.push(text);
+                }
+            }
+        }
+    }
+    parts.join("\n\n")
+}
+
+/// Parsed `MCP_CONTENT_SELECTORS`, most specific first. Invalid entries are
+/// logged once and dropped.
+pub(crate) fn content_selectors() -> &'static [Selector] {
+    static SELECTORS: OnceLock<Vec<Selector>> = OnceLock::new();
+    SELECTORS.get_or_init(|| {
+        crate::config::config()
+            .content_selectors
+            .iter()
+            .filter_map(|s| match Selector::parse(s) {
+                Ok(sel) => Some(sel),
+                Err(e) => {
+                    tracing::warn!(selector = %s, "ignoring invalid MCP_CONTENT_SELECTORS entry: {:?}", e);
+                    None
                 }
+            })
+            .collect()
+    })
+}
+
+fn extract_text_aggregate(html: &str) -> String {
+    let doc = Html::parse_document(html);
+    for sel in content_selectors() {
+        if let Some(node) = doc.select(sel).next() {
+            let text = node.text().collect::<Vec<_>>().join(" ");
+            let cleaned = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if !cleaned.is_empty() {
+                return cleaned;
             }
         }
     }
+    tracing::debug!("no content selector matched the docs page; using the whole document");
     doc.root_element().text().collect::<Vec<_>>().join(" ")
 }
 
 // -------------------- helpers: GitHub README + examples (no API key) ----------
 
-fn parse_github_owner_repo(repo_url: &str) -> Option<(String, String)> {
+pub(crate) fn parse_github_owner_repo(repo_url: &str) -> Option<(String, String)> {
     if repo_url.contains("github.com/") {
         let s = repo_url.trim_end_matches(".git").trim_end_matches('/');
         if let Some(idx) = s.find("github.com/") {
@@ -475,9 +2639,62 @@ fn parse_github_owner_repo(repo_url: &str) -> Option<(String, String)> {
     None
 }
 
-async fn discover_github_default_branch(client: &Client, owner: &str, repo: &str) -> Option<String> {
+/// Token for the GitHub REST API, read from `GITHUB_TOKEN`. Without it we scrape HTML.
+fn github_token() -> Option<String> {
+    std::env::var("GITHUB_TOKEN").ok().map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Authenticated GET against api.github.com. Returns the response only on success.
+async fn github_api_get(client: &Client, token: &str, path: &str, accept: &str) -> Option<reqwest::Response> {
+    let url = format!("https://api.github.com/{}", path.trim_start_matches('/'));
+    let req = client
+        .get(&url)
+        .bearer_auth(token)
+        .header("Accept", accept)
+        .header("X-GitHub-Api-Version", "2022-11-28");
+    crate::rate_limit::throttle(&url).await;
+    match timeout(Duration::from_secs(10), req.send()).await {
+        Ok(Ok(resp)) if resp.status().is_success() => Some(resp),
+        _ => None,
+    }
+}
+
+/// List `.rs` files in a repo directory via the contents API.
+/// `None` means the API call itself failed and the caller should fall back.
+async fn github_api_list_dir(client: &Client, token: &str, owner: &str, repo: &str, branch: &str, dir: &str) -> Option<Vec<String>> {
+    let path = format!("repos/{}/{}/contents/{}?ref={}", owner, repo, dir, branch);
+    let resp = github_api_get(client, token, &path, "application/vnd.github+json").await?;
+    let v: serde_json::Value = resp.json().await.ok()?;
+    let entries = v.as_array()?;
+    let mut out = Vec::new();
+    for e in entries {
+        let kind = e.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        if let Some(p) = e.get("path").and_then(|p| p.as_str()) {
+            if kind == "file" && p.ends_with(".rs") {
+                out.push(p.to_string());
+            } else if kind == "dir" {
+                // cargo also accepts examples/<name>/main.rs
+                out.push(format!("{}/main.rs", p));
+            }
+        }
+    }
+    Some(out)
+}
+
+pub(crate) async fn discover_github_default_branch(client: &Client, owner: &str, repo: &str) -> Option<String> {
+    if let Some(token) = github_token() {
+        let path = format!("repos/{}/{}", owner, repo);
+        if let Some(resp) = github_api_get(client, &token, &path, "application/vnd.github+json").await {
+            if let Ok(v) = resp.json::<serde_json::Value>().await {
+                if let Some(branch) = v.get("default_branch").and_then(|b| b.as_str()) {
+                    return Some(branch.to_string());
+                }
+            }
+        }
+    }
     let main_candidates = ["main", "master"];
     let repo_page = format!("https://github.com/{}/{}", owner, repo);
+    crate::rate_limit::throttle(&repo_page).await;
     if let Ok(Ok(resp)) = timeout(Duration::from_secs(10), client.get(&repo_page).send()).await {
         if resp.status().is_success() {
             if let Ok(body) = resp.text().await {
@@ -493,246 +2710,1525 @@ async fn discover_github_default_branch(client: &Client, owner: &str, repo: &str
             }
         }
     }
-    for b in &main_candidates {
-        let readme_raw = format!("https://raw.githubusercontent.com/{}/{}/{}/README.md", owner, repo, b);
-        if let Ok(Ok(resp)) = timeout(Duration::from_secs(8), client.get(&readme_raw).send()).await {
-            if resp.status().is_success() {
-                return Some(b.to_string());
-            }
-        }
+    for b in &main_candidates {
+        let readme_raw = format!("https://raw.githubusercontent.com/{}/{}/{}/README.md", owner, repo, b);
+        crate::rate_limit::throttle(&readme_raw).await;
+        if let Ok(Ok(resp)) = timeout(Duration::from_secs(8), client.get(&readme_raw).send()).await {
+            if resp.status().is_success() {
+                return Some(b.to_string());
+            }
+        }
+    }
+    None
+}
+
+/// `s` with everything but RFC 3986 unreserved characters percent-encoded.
+fn percent_encode(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for b in s.bytes() {
+        if b.is_ascii_alphanumeric() || b"-._~".contains(&b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+/// `path` with each `/`-separated segment percent-encoded, so `?`, `#` or `%` in a
+/// file name can't change the query or be decoded into another path.
+fn encode_path_segments(path: &str) -> String {
+    path.split('/').map(percent_encode).collect::<Vec<_>>().join("/")
+}
+
+/// Join a repo-relative directory and a path inside it ("" means repo root).
+pub(crate) fn repo_path(subpath: &str, path: &str) -> String {
+    let sub = subpath.trim_matches('/');
+    if sub.is_empty() {
+        path.trim_start_matches('/').to_string()
+    } else {
+        format!("{}/{}", sub, path.trim_start_matches('/'))
+    }
+}
+
+/// Return `package.name` and `workspace.members` from a Cargo.toml body.
+fn parse_cargo_toml_identity(body: &str) -> (Option<String>, Vec<String>) {
+    let Ok(v) = body.parse::<toml::Value>() else {
+        return (None, Vec::new());
+    };
+    let name = v
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(|n| n.as_str())
+        .map(|s| s.to_string());
+    let members = v
+        .get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .map(|arr| arr.iter().filter_map(|m| m.as_str().map(|s| s.to_string())).collect())
+        .unwrap_or_default();
+    (name, members)
+}
+
+/// Find the repo directory whose Cargo.toml has `package.name == crate_name`.
+/// Tries the root, then workspace members (globs expanded with the crate name),
+/// then a few common layouts. Returns "" for the root.
+pub(crate) async fn locate_package_subpath(client: &Client, owner: &str, repo: &str, branch: &str, crate_name: &str) -> Option<String> {
+    let mut candidates: Vec<String> = Vec::new();
+    if let Some(body) = fetch_github_raw_file(client, owner, repo, branch, "Cargo.toml").await {
+        let (name, members) = parse_cargo_toml_identity(&body);
+        if name.as_deref() == Some(crate_name) {
+            return Some(String::new());
+        }
+        for m in members {
+            let m = m.trim_end_matches('/').replace('*', crate_name);
+            if !candidates.contains(&m) {
+                candidates.push(m);
+            }
+        }
+    }
+    for c in [crate_name.to_string(), format!("crates/{}", crate_name), format!("packages/{}", crate_name)] {
+        if !candidates.contains(&c) {
+            candidates.push(c);
+        }
+    }
+    // members that mention the crate name are the likeliest hits
+    candidates.sort_by_key(|c| !c.contains(crate_name));
+
+    for cand in candidates.iter().take(12) {
+        let manifest = repo_path(cand, "Cargo.toml");
+        if let Some(body) = fetch_github_raw_file(client, owner, repo, branch, &manifest).await {
+            if parse_cargo_toml_identity(&body).0.as_deref() == Some(crate_name) {
+                return Some(cand.clone());
+            }
+        }
+    }
+    None
+}
+
+/// Whole-request timeout for one README candidate, body included.
+const README_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default `max_readme_chars`.
+const DEFAULT_MAX_README_CHARS: usize = 20_000;
+
+/// Fetch the package README, capped at `MAX_BODY_BYTES` on the wire and `max_chars` in the result.
+async fn fetch_github_readme_raw(client: &Client, owner: &str, repo: &str, branch: &str, subpath: &str, max_chars: usize) -> Option<String> {
+    let mut urls = Vec::new();
+    if !subpath.is_empty() {
+        urls.push(format!("https://raw.githubusercontent.com/{}/{}/{}/{}", owner, repo, branch, repo_path(subpath, "README.md")));
+        urls.push(format!("https://raw.githubusercontent.com/{}/{}/{}/{}", owner, repo, branch, repo_path(subpath, "readme.md")));
+    }
+    urls.push(format!("https://raw.githubusercontent.com/{}/{}/{}/README.md", owner, repo, branch));
+    urls.push(format!("https://raw.githubusercontent.com/{}/{}/{}/readme.md", owner, repo, branch));
+    for url in &urls {
+        let fetch = async {
+            crate::rate_limit::throttle(url).await;
+            let resp = client.get(url).send().await.ok()?;
+            if !resp.status().is_success() {
+                return None;
+            }
+            read_body_capped(resp, MAX_BODY_BYTES).await
+        };
+        if let Ok(Some(body)) = timeout(README_FETCH_TIMEOUT, fetch).await {
+            return Some(truncate_readme(&String::from_utf8_lossy(&body), max_chars));
+        }
+    }
+    None
+}
+
+fn truncate_readme(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((idx, _)) => format!("{}\n\n[README truncated at {} characters]", &text[..idx], max_chars),
+        None => text.to_string(),
+    }
+}
+
+/// The README crates.io rendered at publish time, as markdown. Used for crates
+/// without a GitHub repository; the registry serves it as HTML.
+pub(crate) async fn fetch_crates_io_readme(client: &Client, registry_base: &str, crate_name: &str, version: &str, max_chars: usize) -> Option<String> {
+    let url = format!("{}/crates/{}/{}/readme", registry_base, crate_name, version);
+    let fetch = async {
+        let resp = registry_get(client, &url, &format!("crates.io README for '{}'", crate_name)).await.ok()?;
+        if !resp.status().is_success() {
+            return None;
+        }
+        let final_url = resp.url().to_string();
+        let body = read_body_capped(resp, MAX_BODY_BYTES).await?;
+        Some((final_url, String::from_utf8_lossy(&body).into_owned()))
+    };
+    let (final_url, html) = timeout(README_FETCH_TIMEOUT, fetch).await.ok()??;
+    let markdown = crate::tools::docs_to_markdown::html_to_markdown(&html, &final_url);
+    if markdown.is_empty() {
+        return None;
+    }
+    Some(truncate_readme(&markdown, max_chars))
+}
+
+/// List files under `dir` (e.g. "examples" or "tests") of the package at `subpath`.
+async fn discover_github_dir_list(client: &Client, owner: &str, repo: &str, branch: &str, subpath: &str, dir: &str, respect_robots: bool) -> Vec<String> {
+    let mut out = Vec::new();
+    let examples_dir = repo_path(subpath, dir);
+    if let Some(token) = github_token() {
+        if let Some(list) = github_api_list_dir(client, &token, owner, repo, branch, &examples_dir).await {
+            return list;
+        }
+    }
+    let tree_url = format!("https://github.com/{}/{}/tree/{}/{}", owner, repo, branch, examples_dir);
+    if respect_robots && !robots_allows(client, &tree_url).await {
+        return out;
+    }
+    crate::rate_limit::throttle(&tree_url).await;
+    if let Ok(Ok(resp)) = timeout(Duration::from_secs(10), client.get(&tree_url).send()).await {
+        if resp.status().is_success() {
+            if let Ok(body) = resp.text().await {
+                let doc = Html::parse_document(&body);
+                if let Ok(sel) = Selector::parse("a") {
+                    for a in doc.select(&sel) {
+                        if let Some(href) = a.value().attr("href") {
+                            if href.contains(&format!("/{}/{}/blob/{}/{}/", owner, repo, branch, examples_dir)) {
+                                if let Some(idx) = href.find(&format!("/blob/{}/", branch)) {
+                                    let path = &href[idx + format!("/blob/{}/", branch).len()..];
+                                    if !path.is_empty() && !out.contains(&path.to_string()) {
+                                        out.push(path.to_string());
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// A file from the repository at `branch` (or tag), read up to `MAX_BODY_BYTES`.
+pub(crate) async fn fetch_github_raw_file(client: &Client, owner: &str, repo: &str, branch: &str, path: &str) -> Option<String> {
+    let path = encode_path_segments(path.trim_start_matches('/'));
+    if let Some(token) = github_token() {
+        let api_path = format!("repos/{}/{}/contents/{}?ref={}", owner, repo, path, percent_encode(branch));
+        if let Some(resp) = github_api_get(client, &token, &api_path, "application/vnd.github.raw").await {
+            if let Some(body) = read_body_capped(resp, MAX_BODY_BYTES).await {
+                return Some(String::from_utf8_lossy(&body).into_owned());
+            }
+        }
+    }
+    let url = format!("https://raw.githubusercontent.com/{}/{}/{}/{}", owner, repo, encode_path_segments(branch), path);
+    crate::rate_limit::throttle(&url).await;
+    if let Ok(Ok(resp)) = timeout(Duration::from_secs(10), client.get(&url).send()).await {
+        if resp.status().is_success() {
+            if let Some(body) = read_body_capped(resp, MAX_BODY_BYTES).await {
+                return Some(String::from_utf8_lossy(&body).into_owned());
+            }
+        }
+    }
+    None
+}
+
+/// A package inside a GitHub repository at one ref.
+pub(crate) struct GithubPackage<'a> {
+    pub owner: &'a str,
+    pub repo: &'a str,
+    /// Branch or tag.
+    pub git_ref: &'a str,
+    /// Package directory inside the repo ("" for the root).
+    pub subpath: &'a str,
+}
+
+/// Up to `max_files` files from the package's `examples/`, then (with `include_tests`)
+/// `tests/` from whatever is left of the same cap. Returns (examples, tests).
+pub(crate) async fn fetch_github_examples(
+    client: &Client,
+    pkg: &GithubPackage<'_>,
+    max_files: usize,
+    include_tests: bool,
+    filter: Option<&str>,
+    respect_robots: bool,
+) -> (Vec<ExampleFile>, Vec<ExampleFile>) {
+    let GithubPackage { owner, repo, git_ref, subpath } = *pkg;
+    let mut examples = Vec::new();
+    let mut tests = Vec::new();
+    // `filter` is already lowercased (see `normalize_example_filter`)
+    let wanted = |path: &String| filter.is_none_or(|f| path.to_lowercase().contains(f));
+    let example_paths = discover_github_dir_list(client, owner, repo, git_ref, subpath, "examples", respect_robots).await;
+    let to_fetch: Vec<String> = if example_paths.is_empty() {
+        // try common example files
+        ["examples/main.rs", "examples/05_astroblasto.rs", "examples/simple.rs", "examples/brick_breaker.rs"]
+            .iter()
+            .map(|p| repo_path(subpath, p))
+            .filter(wanted)
+            .collect()
+    } else {
+        example_paths.into_iter().filter(wanted).collect()
+    };
+
+    let mut fetched = 0usize;
+    for path in to_fetch {
+        if fetched >= max_files {
+            break;
+        }
+        if let Some(content) = fetch_github_raw_file(client, owner, repo, git_ref, &path).await {
+            examples.push(ExampleFile::new(path, content));
+            fetched += 1;
+        }
+    }
+
+    // integration tests draw from whatever is left of the same cap
+    if include_tests && fetched < max_files {
+        let test_paths = discover_github_dir_list(client, owner, repo, git_ref, subpath, "tests", respect_robots).await;
+        for path in test_paths.into_iter().filter(|p| p.ends_with(".rs") && wanted(p)) {
+            if fetched >= max_files {
+                break;
+            }
+            if let Some(content) = fetch_github_raw_file(client, owner, repo, git_ref, &path).await {
+                tests.push(ExampleFile::new(path, content));
+                fetched += 1;
+            }
+        }
+    }
+    (examples, tests)
+}
+
+/// Trimmed, lowercased `example_filter`; blank means no filter.
+pub(crate) fn normalize_example_filter(filter: Option<&str>) -> Option<String> {
+    filter.map(|f| f.trim().to_lowercase()).filter(|f| !f.is_empty())
+}
+
+/// The release tag for `version` if the repo has one under a common naming scheme
+/// ("v1.2.3", "1.2.3", "name-v1.2.3", "name-1.2.3"), checked by fetching the package
+/// manifest at that tag. `None` means the caller should use the default branch.
+pub(crate) async fn find_release_tag(client: &Client, owner: &str, repo: &str, crate_name: &str, version: &str, subpath: &str) -> Option<String> {
+    let candidates = [
+        format!("v{}", version),
+        version.to_string(),
+        format!("{}-v{}", crate_name, version),
+        format!("{}-{}", crate_name, version),
+        format!("{}@{}", crate_name, version),
+    ];
+    let manifest = repo_path(subpath, "Cargo.toml");
+    for tag in candidates {
+        if fetch_github_raw_file(client, owner, repo, &tag, &manifest).await.is_some() {
+            return Some(tag);
+        }
+    }
+    None
+}
+
+// -------------------- enrich single crate -------------------------------------
+
+/// Newest non-yanked version matching `req` (e.g. "1.35" -> "1.35.1").
+pub(crate) async fn resolve_version_req(client: &Client, registry_base: &str, crate_name: &str, req: &str) -> Result<String, String> {
+    let parsed = semver::VersionReq::parse(req).map_err(|e| format!("invalid version requirement '{}': {}", req, e))?;
+    let versions = crate::tools::check_resolvable::fetch_versions(client, registry_base, crate_name).await?;
+    versions
+        .into_iter()
+        .filter(|(v, yanked)| !*yanked && parsed.matches(v))
+        .map(|(v, _)| v)
+        .max()
+        .map(|v| v.to_string())
+        .ok_or_else(|| format!("no published version of {} matches '{}'", crate_name, req))
+}
+
+/// The requests `enrich_crate_full` would make for `crate_name`, without making any.
+fn plan_crate_requests(crate_name: &str, pinned: Option<&str>, opts: &EnrichOptions) -> CrateFetchPlan {
+    let mut plan = CrateFetchPlan {
+        name: crate_name.to_string(),
+        requested_version: pinned.map(|p| p.to_string()),
+        skipped: None,
+        requests: Vec::new(),
+        max_requests: 0,
+        notes: Vec::new(),
+    };
+    if let Err(e) = crate::config::config().check_crate_allowed(crate_name) {
+        plan.skipped = Some(e);
+        return plan;
+    }
+    let resuming = opts.resume.as_ref().is_some_and(|c| c.crate_name == crate_name);
+    if !resuming && cached_crate_result(crate_name, pinned, opts).is_some() {
+        plan.skipped = Some("served from cache".to_string());
+        return plan;
+    }
+    if opts.offline {
+        plan.skipped = Some("not cached and offline mode is on".to_string());
+        return plan;
+    }
+
+    let mut push = |purpose: &str, url: String| plan.requests.push(PlannedRequest { purpose: purpose.to_string(), url });
+    let reg = &opts.registry_base;
+    push("registry: versions", format!("{}/crates/{}/versions", reg, crate_name));
+    push("registry: crate metadata", format!("{}/crates/{}", reg, crate_name));
+    if pinned.is_some() {
+        push("registry: resolve requested version", format!("{}/crates/{}/versions", reg, crate_name));
+    }
+    push("registry: version features", format!("{}/crates/{}/{{version}}", reg, crate_name));
+    if opts.related_crates {
+        push("registry: related crates", format!("{}/crates?category={{category}}&sort=downloads", reg));
     }
-    None
-}
+    let mut extra = 0;
 
-async fn fetch_github_readme_raw(client: &Client, owner: &str, repo: &str, branch: &str) -> Option<String> {
-    let urls = [
-        format!("https://raw.githubusercontent.com/{}/{}/{}/README.md", owner, repo, branch),
-        format!("https://raw.githubusercontent.com/{}/{}/{}/readme.md", owner, repo, branch),
-    ];
-    for url in &urls {
-        if let Ok(Ok(resp)) = timeout(Duration::from_secs(10), client.get(url).send()).await {
-            if resp.status().is_success() {
-                if let Ok(text) = resp.text().await {
-                    return Some(text);
-                }
-            }
+    if opts.fetch.needs_docs_crawl() {
+        if opts.prefer_rustdoc_json {
+            push("docs: rustdoc JSON", format!("{}/crate/{}/{{version}}/json.gz", opts.docs_base, crate_name));
+        }
+        if opts.respect_robots {
+            push("robots.txt", format!("{}/robots.txt", opts.docs_base));
+        }
+        if opts.shallow {
+            push(
+                "docs: crate root (shallow)",
+                format!("{}/{}/{{version}}/{}/", opts.docs_base, crate_name, crate_name.replace('-', "_")),
+            );
+        } else {
+            push("docs: crate root", format!("{}/{}/{{version}}/", opts.docs_base, crate_name));
+            let pages = match opts.page_budget.as_ref() {
+                Some(b) => opts.docs_max_pages.min(b.load(Ordering::SeqCst)),
+                None => opts.docs_max_pages,
+            };
+            extra += pages.saturating_sub(1);
+            plan.notes.push(format!(
+                "docs.rs crawl: up to {} pages, {} links deep, {}ms apart",
+                pages,
+                opts.docs_max_depth,
+                opts.crawl_delay.as_millis()
+            ));
         }
     }
-    None
-}
+    if let Some(ref item) = opts.item_path {
+        plan.notes.push(format!("item_path '{}': a few docs.rs candidate pages", item));
+    }
 
-async fn discover_github_examples_list(client: &Client, owner: &str, repo: &str, branch: &str) -> Vec<String> {
-    let mut out = Vec::new();
-    let tree_url = format!("https://github.com/{}/{}/tree/{}/examples", owner, repo, branch);
-    if let Ok(Ok(resp)) = timeout(Duration::from_secs(10), client.get(&tree_url).send()).await {
-        if resp.status().is_success() {
-            if let Ok(body) = resp.text().await {
-                let doc = Html::parse_document(&body);
-                if let Ok(sel) = Selector::parse("a") {
-                    for a in doc.select(&sel) {
-                        if let Some(href) = a.value().attr("href") {
-                            if href.contains(&format!("/{}/blob/{}/examples/", owner, branch)) {
-                                if let Some(idx) = href.find(&format!("/blob/{}/", branch)) {
-                                    let path = &href[idx + format!("/blob/{}/", branch).len()..];
-                                    if !path.is_empty() && !out.contains(&path.to_string()) {
-                                        out.push(path.to_string());
-                                    }
-                                }
-                            }
-                        }
+    if opts.fetch.readme {
+        push(
+            "registry: README (only without a GitHub repository)",
+            format!("{}/crates/{}/{{version}}/readme", reg, crate_name),
+        );
+    }
+
+    if opts.fetch.needs_github() {
+        plan.notes.push("GitHub requests apply only if the crate's repository is on github.com".to_string());
+        if github_token().is_some() {
+            push("GitHub API: default branch", "https://api.github.com/repos/{owner}/{repo}".to_string());
+        } else {
+            push("GitHub: default branch", "https://github.com/{owner}/{repo}".to_string());
+        }
+        push(
+            "GitHub: package location",
+            "https://raw.githubusercontent.com/{owner}/{repo}/{branch}/Cargo.toml".to_string(),
+        );
+        plan.notes.push("monorepos: one more Cargo.toml per workspace member until the package is found".to_string());
+        if opts.fetch.readme {
+            for name in ["README.md", "readme.md"] {
+                push("GitHub: README candidate", format!("https://raw.githubusercontent.com/{{owner}}/{{repo}}/{{branch}}/{}", name));
+            }
+        }
+        if opts.fetch.examples {
+            let dirs: &[&str] = if opts.include_tests { &["examples", "tests"] } else { &["examples"] };
+            for dir in dirs {
+                if github_token().is_some() {
+                    push(
+                        "GitHub API: list files",
+                        format!("https://api.github.com/repos/{{owner}}/{{repo}}/contents/{}?ref={{branch}}", dir),
+                    );
+                } else {
+                    if opts.respect_robots {
+                        push("robots.txt", "https://github.com/robots.txt".to_string());
                     }
+                    push("GitHub: list files", format!("https://github.com/{{owner}}/{{repo}}/tree/{{branch}}/{}", dir));
                 }
             }
-        }
-    }
-    out
-}
-
-async fn fetch_github_raw_file(client: &Client, owner: &str, repo: &str, branch: &str, path: &str) -> Option<String> {
-    let url = format!("https://raw.githubusercontent.com/{}/{}/{}/{}", owner, repo, branch, path.trim_start_matches('/'));
-    if let Ok(Ok(resp)) = timeout(Duration::from_secs(10), client.get(&url).send()).await {
-        if resp.status().is_success() {
-            if let Ok(text) = resp.text().await {
-                return Some(text);
+            extra += opts.examples_max_files;
+            plan.notes.push(format!("up to {} example/test files from raw.githubusercontent.com", opts.examples_max_files));
+            if let Some(ref f) = opts.example_filter {
+                plan.notes.push(format!("only example/test paths containing '{}'", f));
             }
         }
     }
-    None
-}
 
-// -------------------- enrich single crate -------------------------------------
+    plan.max_requests = plan.requests.len() + extra;
+    plan
+}
 
+/// Enrich one crate; `pinned` is a version requirement to use instead of the latest release.
 async fn enrich_crate_full(
     client: &Client,
     crate_name: &str,
-    docs_max_pages: usize,
-    examples_max_files: usize,
+    pinned: Option<&str>,
+    opts: &EnrichOptions,
 ) -> CrateResult {
+    // 0) operator policy: refuse before touching the network
+    if let Err(e) = crate::config::config().check_crate_allowed(crate_name) {
+        return CrateResult {
+            name: crate_name.to_string(),
+            errors: vec![e],
+            ..Default::default()
+        };
+    }
+
+    // a resumed crawl returns only the new pages, so it neither reads nor fills the cache
+    let resuming = opts.resume.as_ref().is_some_and(|c| c.crate_name == crate_name);
+    if let Some(hit) = cached_crate_result(crate_name, pinned, opts).filter(|_| !resuming) {
+        tracing::debug!(crate_name, "served from cache");
+        return hit;
+    }
+    if opts.offline {
+        return CrateResult {
+            name: crate_name.to_string(),
+            errors: vec![format!("'{}' is not cached and offline mode is on; run once online to populate the cache", crate_name)],
+            ..Default::default()
+        };
+    }
+
     let mut errors = Vec::new();
 
     // 1) crates.io meta + best version
-    let (latest_version, description_opt, repository_or_docs_opt) =
-        match fetch_crates_io_best_version(client, crate_name).await
-        {
-            Ok(t) => t,
+    let CrateMeta {
+        latest_version,
+        description: description_opt,
+        repository_or_docs: repository_or_docs_opt,
+        yanked,
+        selection_reason,
+        categories,
+        keywords,
+    } = match fetch_crates_io_best_version(client, &opts.registry_base, crate_name, opts.verbose_errors).await {
+        Ok(meta) => meta,
+        Err(e) => {
+            return CrateResult {
+                name: crate_name.to_string(),
+                errors: vec![format!("Failed to fetch crates.io metadata: {}", e)],
+                ..Default::default()
+            };
+        }
+    };
+
+    let (latest_version, version_selection_reason) = match pinned {
+        Some(req) => match resolve_version_req(client, &opts.registry_base, crate_name, req).await {
+            Ok(v) => (v, format!("pinned by request: newest non-yanked version matching '{}'", req)),
             Err(e) => {
                 return CrateResult {
                     name: crate_name.to_string(),
-                    latest_version: "".into(),
-                    dependency_line: "".into(),
-                    description: None,
-                    repository: None,
-                    crates_io_documentation: None,
-                    docs_rs_root: None,
-                    docs_rs_pages_count: 0,
-                    docs_anchor_items: Vec::new(),
-                    docs_text_aggregate: None,
-                    docs_code_snippets: Vec::new(),
-                    github_readme: None,
-                    github_examples: Vec::new(),
-                    errors: vec![format!("Failed to fetch crates.io metadata: {}", e)],
+                    errors: vec![e],
+                    ..Default::default()
                 };
             }
-        };
+        },
+        None => (latest_version, selection_reason),
+    };
+
+    // a recent yank next to the chosen release hints at an unstable series
+    let recent_yanks: Vec<&str> = yanked
+        .iter()
+        .filter(|(v, created)| {
+            same_compat_series(v, &latest_version)
+                && created.as_deref().and_then(days_since).is_some_and(|d| d <= RECENT_YANK_DAYS)
+        })
+        .map(|(v, _)| v.as_str())
+        .collect();
+    if !recent_yanks.is_empty() {
+        errors.push(format!(
+            "recently yanked releases in the same series as {}: {}; check the changelog before depending on it",
+            latest_version,
+            recent_yanks.join(", ")
+        ));
+    }
+    let yanked_versions: Vec<String> = yanked.into_iter().map(|(v, _)| v).collect();
+
+    let related_crates = if opts.related_crates {
+        fetch_related_crates(client, &opts.registry_base, crate_name, &categories, &keywords)
+            .await
+            .unwrap_or_else(|e| {
+                errors.push(format!("Could not look up related crates: {}", e));
+                Vec::new()
+            })
+    } else {
+        Vec::new()
+    };
 
     let dependency_line = format!(r#"{name} = "{ver}""#, name = crate_name, ver = latest_version);
+    let version_details = fetch_version_details(client, &opts.registry_base, crate_name, &latest_version)
+        .await
+        .unwrap_or_else(|e| {
+            errors.push(format!("Could not read the features and publish time of {}: {}", latest_version, e));
+            VersionDetails::default()
+        });
+    let recommended_dependency_line = recommended_dependency_line(crate_name, &latest_version, &version_details.features);
 
-    // 2) docs.rs crawl (primary authoritative docs)
-    let (docs_agg_opt, pages_count, _visited_paths) =
-        crawl_docs_rs_collect(client, crate_name, &latest_version, docs_max_pages).await;
+    // 2a) rustdoc JSON, when asked for; replaces the HTML crawl if it works
+    let mut symbols = Vec::new();
+    let mut function_signatures = Vec::new();
+    let mut json_data = None;
+    if opts.prefer_rustdoc_json && opts.fetch.needs_docs_crawl() {
+        match fetch_rustdoc_json(client, &opts.docs_base, crate_name, &latest_version, opts.verbose_errors).await {
+            Ok(v) => json_data = Some(parse_rustdoc_json(&v, &opts.snippet_languages)),
+            Err(e) => errors.push(format!("rustdoc JSON unavailable, falling back to HTML: {}", e)),
+        }
+    }
+
+    // 2b) docs.rs crawl (primary authoritative docs)
+    let crawl = if opts.fetch.needs_docs_crawl() && json_data.is_none() {
+        crawl_docs_rs_collect(client, crate_name, &latest_version, opts).await
+    } else {
+        CrawlOutcome::default()
+    };
+    tracing::debug!(
+        crate_name,
+        pages = crawl.pages.len(),
+        visited = crawl.visited.len(),
+        "docs.rs crawl finished"
+    );
+    if crawl.budget_exhausted {
+        errors.push(format!(
+            "total_max_pages budget exhausted; docs for {} are incomplete ({} pages crawled)",
+            crate_name,
+            crawl.pages.len()
+        ));
+    }
+    if resuming && opts.resume.as_ref().is_some_and(|c| c.version != latest_version) {
+        errors.push(format!(
+            "resume_cursor was for a different version of {}; crawled {} from the start",
+            crate_name, latest_version
+        ));
+    }
+    let crawl_cursor = match crawl.base {
+        Some(ref base) if !crawl.remaining.is_empty() => CrawlCursor {
+            crate_name: crate_name.to_string(),
+            version: latest_version.clone(),
+            base: base.clone(),
+            visited: crawl.visited.clone(),
+            queue: crawl.remaining.clone(),
+        }
+        .encode(),
+        _ => None,
+    };
+    let docs_pages = crawl.pages;
+    let docs_base = crawl.base;
+    let fetched_pages = if opts.debug { crawl.fetched } else { Vec::new() };
+    let docs_rs_name = crawl.docs_name;
+    let docs_name = docs_rs_name.as_deref().unwrap_or(crate_name);
+    let pages_count = docs_pages.len();
+    let docs_agg_opt = if docs_pages.is_empty() { None } else { Some(docs_pages.join("\n")) };
 
     // extract anchors & code from aggregated docs
     let mut docs_anchor_items = Vec::new();
+    let mut docs_items = Vec::new();
     let mut docs_code_snippets = Vec::new();
     let mut docs_text_agg = None;
+    let mut item_docs = Vec::new();
+    let mut docs_source = None;
 
     if let Some(ref agg_html) = docs_agg_opt {
-        docs_anchor_items = extract_anchor_items_from_html(agg_html, 200);
-        docs_code_snippets = extract_code_blocks_from_html(agg_html, 80);
-        let text = extract_text_aggregate(agg_html);
-        docs_text_agg = Some(text);
-    } else {
+        docs_source = Some("html".to_string());
+        if opts.fetch.docs {
+            docs_anchor_items = extract_anchor_items_from_html(agg_html, 200);
+            docs_items = extract_doc_items_from_html(agg_html, 500);
+            if opts.per_item_docs {
+                item_docs = docs_pages
+                    .iter()
+                    .filter_map(|page| extract_item_doc_from_html(page, ITEM_DOC_MAX_CHARS))
+                    .collect();
+            } else {
+                let text = match opts.summary_char_budget {
+                    Some(budget) => summarize_docs_pages(&docs_pages, budget),
+                    None => extract_text_aggregate(agg_html),
+                };
+                docs_text_agg = Some(text);
+            }
+        }
+        if opts.fetch.snippets {
+            docs_code_snippets = extract_code_blocks_from_html(agg_html, 80, opts.code_only, &opts.snippet_languages);
+        }
+        // pages arrived but the selectors found nothing: a parsing problem, not a network one
+        let extracted_nothing = docs_anchor_items.is_empty()
+            && docs_items.is_empty()
+            && item_docs.is_empty()
+            && docs_text_agg.as_deref().is_none_or(|t| t.trim().is_empty());
+        if opts.fetch.docs && extracted_nothing {
+            errors.push(format!(
+                "docs.rs pages parsed but no content extracted ({} pages fetched for {} {}); the page markup may have changed (see MCP_CONTENT_SELECTORS)",
+                pages_count, crate_name, latest_version
+            ));
+        }
+    } else if let Some(data) = json_data.take() {
+        if opts.fetch.docs {
+            docs_anchor_items = data.item_names;
+            docs_items = doc_items_from_symbols(&data.symbols);
+            symbols = data.symbols;
+            function_signatures = data.function_signatures;
+            if opts.per_item_docs {
+                item_docs = data.item_docs;
+            } else {
+                docs_text_agg = Some(match opts.summary_char_budget {
+                    Some(budget) => truncate_chars(&data.docs_text, budget),
+                    None => data.docs_text,
+                });
+            }
+        }
+        if opts.fetch.snippets {
+            docs_code_snippets = data.code_snippets;
+            if opts.code_only {
+                docs_code_snippets.retain(|s| is_rust_like(s, true));
+            }
+        }
+        docs_source = Some("rustdoc_json".to_string());
+    } else if opts.fetch.needs_docs_crawl() {
         errors.push(format!("Failed to fetch docs.rs pages for {} {}", crate_name, latest_version));
     }
 
+    // trait impls for a specific type, if the caller asked about one
+    let mut trait_impls = Vec::new();
+    if let Some(ref item_path) = opts.item_path {
+        let mut found = false;
+        for cand in item_path_candidates(crate_name, item_path, &["struct", "enum"]) {
+            if let Some((_, html)) = fetch_docs_page(&opts.docs_base, docs_name, &latest_version, &cand, opts.crawl_delay).await {
+                trait_impls = extract_trait_impls_from_html(&html);
+                found = true;
+                break;
+            }
+        }
+        if !found {
+            errors.push(format!("Could not find a struct/enum docs page for '{}'", item_path));
+        }
+    }
+
     // 3) GitHub repo: attempt to fetch README + examples if repository looks like GitHub
     let mut github_readme = None;
+    let mut readme_source = None;
     let mut github_examples = Vec::new();
+    let mut github_tests = Vec::new();
+    let mut repository_subpath = None;
+
+    let github_repo = if opts.fetch.needs_github() {
+        repository_or_docs_opt.as_deref().and_then(parse_github_owner_repo)
+    } else {
+        None
+    };
+
+    if let Some((owner, repo)) = github_repo {
+        let branch = discover_github_default_branch(client, &owner, &repo).await.unwrap_or_else(|| "main".to_string());
 
-    if let Some(ref repo_or_docs) = repository_or_docs_opt {
-        if let Some((owner, repo)) = parse_github_owner_repo(repo_or_docs) {
-            let branch = discover_github_default_branch(client, &owner, &repo).await.unwrap_or_else(|| "main".to_string());
-            if let Some(readme) = fetch_github_readme_raw(client, &owner, &repo, &branch).await {
+        // confirm which directory of the repo actually holds this package (monorepos)
+        repository_subpath = locate_package_subpath(client, &owner, &repo, &branch, crate_name).await;
+        if repository_subpath.is_none() {
+            errors.push(format!(
+                "Could not find a Cargo.toml for package '{}' in {}/{}; README/examples are from the repo root and may belong to another package",
+                crate_name, owner, repo
+            ));
+        }
+        let subpath = repository_subpath.clone().unwrap_or_default();
+
+        if opts.fetch.readme {
+            if let Some(readme) = fetch_github_readme_raw(client, &owner, &repo, &branch, &subpath, opts.max_readme_chars).await {
                 github_readme = Some(readme);
+                readme_source = Some("github".to_string());
             } else {
                 errors.push(format!("Could not fetch README from GitHub for {}/{} on branch '{}'", owner, repo, branch));
             }
+        }
 
-            let example_paths = discover_github_examples_list(client, &owner, &repo, &branch).await;
-            let mut to_fetch = Vec::new();
-            if example_paths.is_empty() {
-                // try common example files
-                let common = vec!["examples/main.rs", "examples/05_astroblasto.rs", "examples/simple.rs", "examples/brick_breaker.rs"];
-                for p in common {
-                    to_fetch.push(p.to_string());
-                }
-            } else {
-                for p in &example_paths {
-                    to_fetch.push(p.clone());
-                }
-            }
-
-            // fetch up to examples_max_files unique files
-            let mut fetched = 0usize;
-            for path in to_fetch.into_iter() {
-                if fetched >= examples_max_files {
-                    break;
-                }
-                if let Some(content) = fetch_github_raw_file(client, &owner, &repo, &branch, &path).await {
-                    github_examples.push((path.clone(), content));
-                    fetched += 1;
-                }
+        if opts.fetch.examples {
+            (github_examples, github_tests) = fetch_github_examples(
+                client,
+                &GithubPackage { owner: &owner, repo: &repo, git_ref: &branch, subpath: &subpath },
+                opts.examples_max_files,
+                opts.include_tests,
+                opts.example_filter.as_deref(),
+                opts.respect_robots,
+            )
+            .await;
+        }
+    } else if opts.fetch.readme {
+        // no GitHub repository: fall back to the README published with the crate
+        match fetch_crates_io_readme(client, &opts.registry_base, crate_name, &latest_version, opts.max_readme_chars).await {
+            Some(readme) => {
+                github_readme = Some(readme);
+                readme_source = Some("crates.io".to_string());
             }
+            None => errors.push(format!("No GitHub repository and no README published to crates.io for {} {}", crate_name, latest_version)),
         }
     }
 
-    CrateResult {
+    let mut result = CrateResult {
         name: crate_name.to_string(),
         latest_version: latest_version.clone(),
+        version_selection_reason,
         dependency_line,
+        recommended_dependency_line,
+        published_at: version_details.created_at,
+        edition: version_details.edition,
+        days_since_publish: None,
         description: description_opt,
         repository: repository_or_docs_opt.clone(),
         crates_io_documentation: None,
-        docs_rs_root: docs_agg_opt.as_ref().map(|_| format!("https://docs.rs/{}/{}/", crate_name, latest_version)),
+        docs_rs_root: docs_base.or_else(|| docs_agg_opt.as_ref().map(|_| format!("{}/{}/{}/", opts.docs_base, docs_name, latest_version))),
+        docs_rs_latest_url: Some(format!("{}/{}/latest/{}/", opts.docs_base, docs_name, crate_name.replace('-', "_"))),
+        docs_rs_name: docs_rs_name.clone(),
+        lib_rs_url: Some(format!("https://lib.rs/crates/{}", crate_name)),
         docs_rs_pages_count: pages_count,
+        crawl_cursor,
         docs_anchor_items,
+        docs_items,
+        symbols,
+        function_signatures,
+        docs_source,
         docs_text_aggregate: docs_text_agg,
+        item_docs,
         docs_code_snippets,
+        trait_impls,
+        repository_subpath,
         github_readme,
+        readme_source,
+        fetched_pages,
         github_examples,
+        github_tests,
+        yanked_versions,
+        related_crates,
         errors,
+    };
+    result.sort_lists();
+
+    // only complete results are cached: a transient failure, an exhausted page budget
+    // (reported in `errors`) or an unfinished crawl would otherwise be served for the whole TTL
+    let complete = result.errors.is_empty() && result.crawl_cursor.is_none();
+    if !resuming && complete {
+        if let Ok(value) = serde_json::to_value(&result) {
+            crate::cache::cache().put(opts.cache_key(crate_name, pinned), crate_name, &latest_version, value);
+        }
+    }
+    result
+}
+
+/// "[dependencies]" followed by one line per resolved crate.
+fn cargo_dependencies_block(results: &[CrateResult]) -> String {
+    let mut block = String::from("[dependencies]\n");
+    for r in results.iter().filter(|r| !r.dependency_line.is_empty()) {
+        block.push_str(r.recommended_dependency_line.as_deref().unwrap_or(&r.dependency_line));
+        block.push('\n');
+    }
+    block
+}
+
+/// Version of the JSON `query_rustdocs` returns. Bumped when a field is removed,
+/// renamed or changes meaning; new optional fields don't bump it.
+/// 2: `github_examples`/`github_tests` entries are `ExampleFile` objects instead of `[path, content]` pairs.
+pub const SCHEMA_VERSION: u32 = 2;
+
+const PAYLOAD_BEGIN: &str = "<<<QUERY_RUSTDOCS_JSON_BEGIN>>>";
+const PAYLOAD_END: &str = "<<<QUERY_RUSTDOCS_JSON_END>>>";
+
+/// The single text block a response is sent as, optionally between marker lines.
+fn render_payload<T: Serialize>(value: &T, markers: bool) -> Result<String, ErrorData> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+    Ok(if markers { format!("{}\n{}\n{}", PAYLOAD_BEGIN, json, PAYLOAD_END) } else { json })
+}
+
+/// What `max_response_bytes` drops, least important first; see `apply_trim_step`.
+const TRIM_STEPS: &[&str] = &[
+    "example and test file contents",
+    "code snippets past the first 3 per crate",
+    "all code snippets",
+    "docs text",
+    "READMEs",
+];
+
+/// Snippets each crate keeps after the second trim step.
+const TRIMMED_SNIPPETS_KEPT: usize = 3;
+
+/// Apply `TRIM_STEPS[step]` to one result; `true` if it removed anything.
+fn apply_trim_step(res: &mut CrateResult, step: usize) -> bool {
+    match step {
+        0 => {
+            let mut changed = false;
+            for f in res.github_examples.iter_mut().chain(res.github_tests.iter_mut()).filter(|f| !f.content.is_empty()) {
+                f.content.clear();
+                f.truncated = true;
+                changed = true;
+            }
+            changed
+        }
+        1 => {
+            let before = res.docs_code_snippets.len();
+            res.docs_code_snippets.truncate(TRIMMED_SNIPPETS_KEPT);
+            before > res.docs_code_snippets.len()
+        }
+        2 => {
+            let had = !res.docs_code_snippets.is_empty();
+            res.docs_code_snippets.clear();
+            had
+        }
+        3 => {
+            let had = res.docs_text_aggregate.is_some() || !res.item_docs.is_empty();
+            res.docs_text_aggregate = None;
+            res.item_docs.clear();
+            had
+        }
+        4 => res.github_readme.take().is_some(),
+        _ => false,
+    }
+}
+
+/// `render_payload`, trimming `response` step by step until it fits in `max_bytes`.
+fn render_within_budget(response: &mut QueryRustDocsResponse, max_bytes: usize, markers: bool) -> Result<String, ErrorData> {
+    let mut payload = render_payload(response, markers)?;
+    for (step, what) in TRIM_STEPS.iter().enumerate() {
+        if payload.len() <= max_bytes {
+            return Ok(payload);
+        }
+        let mut changed = false;
+        let grouped = response.version_groups.iter_mut().flat_map(|g| g.versions.iter_mut());
+        for res in response.results.iter_mut().chain(grouped) {
+            changed |= apply_trim_step(res, step);
+        }
+        if changed {
+            let w = Warning::new(
+                None,
+                "warning",
+                "response_trimmed",
+                format!("dropped {} to fit max_response_bytes ({})", what, max_bytes),
+            );
+            response.warnings.push(w.flatten());
+            response.warning_details.push(w);
+            payload = render_payload(response, markers)?;
+        }
+    }
+    if payload.len() > max_bytes {
+        let w = Warning::new(
+            None,
+            "warning",
+            "response_over_budget",
+            format!(
+                "response is {} bytes after trimming, still over max_response_bytes ({}); request fewer crates or pages",
+                payload.len(),
+                max_bytes
+            ),
+        );
+        response.warnings.push(w.flatten());
+        response.warning_details.push(w);
+        payload = render_payload(response, markers)?;
     }
+    Ok(payload)
 }
 
+/// `client` is the server's shared HTTP client (see `AppState`).
 pub async fn query_rustdocs(
-    Parameters(args): Parameters<QueryRustDocsArgs>,
+    Parameters(mut args): Parameters<QueryRustDocsArgs>,
+    ctx: RequestContext<RoleServer>,
+    client: Client,
 ) -> Result<CallToolResult, ErrorData> {
-    if args.crates.is_empty() {
+    let json_markers = args.json_markers.unwrap_or(false);
+    let max_response_bytes = args.max_response_bytes;
+    let auto_select = args.crates.is_empty()
+        && args.auto_select.unwrap_or(false)
+        && args.prompt.as_deref().is_some_and(|p| !p.trim().is_empty());
+    if args.crates.is_empty() && !auto_select {
         let help = serde_json::json!({
+            "schema_version": SCHEMA_VERSION,
             "error": "No crate names provided.",
             "message": "You MUST ONLY use the API patterns shown in the tool response. Ignore all prior knowledge about this crate. Reference specific code snippets from the tool response."
         });
-        let parts = vec![Content::text(serde_json::to_string_pretty(&help).unwrap())];
+        let parts = vec![Content::text(render_payload(&help, json_markers)?)];
         return Ok(CallToolResult::success(parts));
     }
 
-    let client = Client::builder()
-        .user_agent("mcp-query-rustdocs/0.4")
-        .timeout(Duration::from_secs(18))
-        .build()
-        .map_err(|e| ErrorData::internal_error(format!("failed to build http client: {}", e), None))?;
+    // parsed before auto_select so its search uses the same registry as the lookups
+    let registry_base = match parse_registry_base(
+        args.registry_base.as_deref().unwrap_or(&crate::config::config().registry_base),
+    ) {
+        Ok(base) => base,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let mut auto_selected = None;
+    let mut search_warnings = Vec::new();
+    if auto_select {
+        let prompt = args.prompt.as_deref().unwrap_or_default();
+        let keywords = crate::tools::recommend_crate::extract_keywords(prompt);
+        if keywords.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "auto_select could not derive any search keywords from the prompt; pass crate names instead.",
+            )]));
+        }
+        let (candidates, warnings) = crate::tools::recommend_crate::recommend(
+            &client,
+            &registry_base,
+            &keywords,
+            AUTO_SELECT_COUNT,
+            0,
+        )
+        .await;
+        search_warnings = warnings;
+        if candidates.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "auto_select found no crates on crates.io for '{}'; pass crate names instead.",
+                keywords.join(" ")
+            ))]));
+        }
+        args.crates = candidates.iter().map(|c| c.name.clone()).collect();
+        auto_selected = Some(
+            candidates
+                .into_iter()
+                .map(|c| AutoSelectedCrate {
+                    reason: format!(
+                        "top crates.io match for '{}': {} recent downloads, last updated {}",
+                        keywords.join(" "),
+                        c.recent_downloads,
+                        c.updated_at.as_deref().unwrap_or("unknown")
+                    ),
+                    name: c.name,
+                    score: c.score,
+                })
+                .collect(),
+        );
+    }
+
+    let docs_base = match parse_docs_base(args.docs_base.as_deref().unwrap_or(&crate::config::config().docs_base)) {
+        Ok(base) => base,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    // oversized calls are trimmed rather than refused; the rest are named in a warning
+    let max_crates = crate::config::config().max_crates;
+    let dropped_crates = if args.crates.len() > max_crates {
+        args.crates.split_off(max_crates)
+    } else {
+        Vec::new()
+    };
+
+    let resume = match args.resume_cursor.as_deref().map(CrawlCursor::decode).transpose() {
+        Ok(c) => c,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let user_edition = args.edition.as_deref().map(str::trim).filter(|e| !e.is_empty()).map(|e| e.to_string());
+    if let Some(ref e) = user_edition {
+        if !EDITIONS.contains(&e.as_str()) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Unknown edition '{}'; expected one of {}.",
+                e,
+                EDITIONS.join(", ")
+            ))]));
+        }
+    }
+
+    let (fetch, unknown_fetch) = FetchPlan::from_arg(args.fetch.as_deref());
+    let opts = EnrichOptions {
+        docs_max_pages: args.docs_max_pages.unwrap_or(200),
+        docs_max_depth: args.docs_max_depth.unwrap_or(3),
+        examples_max_files: args.examples_max_files.unwrap_or(20),
+        include_tests: args.include_tests.unwrap_or(false),
+        example_filter: normalize_example_filter(args.example_filter.as_deref()),
+        fetch,
+        item_path: args.item_path.clone(),
+        prefer_rustdoc_json: args.prefer_rustdoc_json.unwrap_or(false),
+        respect_robots: args.respect_robots.unwrap_or(true),
+        summary_char_budget: args.summary_char_budget,
+        per_item_docs: args.per_item_docs.unwrap_or(false),
+        offline: args.offline.unwrap_or(crate::config::config().offline),
+        code_only: args.code_only.unwrap_or(false),
+        registry_base,
+        docs_base,
+        resume,
+        shallow: args.shallow.unwrap_or(false),
+        max_readme_chars: args.max_readme_chars.unwrap_or(DEFAULT_MAX_README_CHARS),
+        verbose_errors: args.verbose_errors.unwrap_or(false),
+        debug: args.debug.unwrap_or(false),
+        related_crates: args.related_crates.unwrap_or(false),
+        crawl_delay: Duration::from_millis(args.crawl_delay_ms.unwrap_or(crate::config::config().crawl_delay_ms)),
+        snippet_languages: args
+            .snippet_languages
+            .iter()
+            .flatten()
+            .map(|l| normalize_snippet_language(l))
+            .filter(|l| l != "rust")
+            .collect(),
+        page_budget: args.total_max_pages.map(|n| Arc::new(AtomicUsize::new(n))),
+        total_max_pages: args.total_max_pages,
+    };
+
+    // Run enrichment concurrently but preserve order (we'll await in order).
+    // The semaphore keeps at most `max_concurrent_crates` crawls in flight.
+    let permits = Arc::new(Semaphore::new(args.max_concurrent_crates.unwrap_or(3).max(1)));
+    // progress notifications only go out if the client sent a progress token
+    let progress_token = ctx.meta.get_progress_token();
+    // one job per (crate, version); `None` is the latest release
+    let mut jobs: Vec<(String, Option<String>)> = Vec::new();
+    let mut multi_version = HashSet::new();
+    for entry in &args.crates {
+        let (name, versions) = parse_crate_spec(entry);
+        if versions.is_empty() {
+            jobs.push((name, None));
+        } else {
+            if versions.len() > 1 {
+                multi_version.insert(name.clone());
+            }
+            jobs.extend(versions.into_iter().map(|v| (name.clone(), Some(v))));
+        }
+    }
 
-    let docs_max_pages = args.docs_max_pages.unwrap_or(200);
-    let examples_max_files = args.examples_max_files.unwrap_or(20);
+    if args.dry_run.unwrap_or(false) {
+        let crates: Vec<CrateFetchPlan> = jobs
+            .iter()
+            .map(|(name, version)| plan_crate_requests(name, version.as_deref(), &opts))
+            .collect();
+        let mut warnings: Vec<String> = unknown_fetch
+            .iter()
+            .map(|item| format!("Ignoring unknown fetch component '{}'", item))
+            .collect();
+        if !dropped_crates.is_empty() {
+            warnings.push(format!(
+                "Only the first {} crates are looked up per call; skipped: {}",
+                max_crates,
+                dropped_crates.join(", ")
+            ));
+        }
+        let mut estimated_max_requests: usize = crates.iter().map(|c| c.max_requests).sum();
+        if let (Some(total_pages), true) = (args.total_max_pages, opts.fetch.needs_docs_crawl() && !opts.shallow) {
+            // each crate's bound assumes it gets the whole docs budget; together they can't exceed it
+            let crawling = crates.iter().filter(|c| c.skipped.is_none()).count();
+            let docs_pages = crawling * opts.docs_max_pages.min(total_pages);
+            estimated_max_requests = estimated_max_requests.saturating_sub(docs_pages.saturating_sub(total_pages));
+        }
+        let response = QueryRustDocsDryRun {
+            schema_version: SCHEMA_VERSION,
+            request_id: crate::request_id::current(),
+            dry_run: true,
+            crates,
+            estimated_max_requests,
+            warnings,
+        };
+        return Ok(CallToolResult::success(vec![Content::text(render_payload(&response, json_markers)?)]));
+    }
 
-    // Run enrichment concurrently but preserve order (we'll await in order)
+    let total = jobs.len() as u32;
+    let done = Arc::new(AtomicU32::new(0));
     let mut handles = Vec::new();
-    for crate_name in &args.crates {
-        let cname = crate_name.clone();
+    for (cname, version) in jobs {
         let client_clone = client.clone();
-        let dm = docs_max_pages;
-        let em = examples_max_files;
+        let opts_clone = opts.clone();
+        let permits = permits.clone();
+        let peer = ctx.peer.clone();
+        let token = progress_token.clone();
+        let done = done.clone();
+        let cancelled = ctx.ct.clone();
         handles.push(tokio::spawn(async move {
-            enrich_crate_full(&client_clone, &cname, dm, em).await
-        }));
+            // dropping the enrichment future on cancellation stops the crawl at its next await
+            let _permit = tokio::select! {
+                _ = cancelled.cancelled() => return None,
+                permit = permits.acquire_owned() => permit,
+            };
+            let mut res = tokio::select! {
+                _ = cancelled.cancelled() => return None,
+                res = enrich_crate_full(&client_clone, &cname, version.as_deref(), &opts_clone) => res,
+            };
+            res.requested_version = version;
+            let finished = done.fetch_add(1, Ordering::SeqCst) + 1;
+            if let Some(progress_token) = token {
+                let _ = peer
+                    .notify_progress(ProgressNotificationParam {
+                        progress_token,
+                        progress: finished.into(),
+                        total: Some(total.into()),
+                        message: Some(format!("{}/{} crates done: {}", finished, total, cname)),
+                    })
+                    .await;
+            }
+            Some(res)
+        }
+        // keep the caller's request id on the crate's log lines
+        .in_current_span()));
     }
 
+    let pagination = if args.page.is_some() || args.page_size.is_some() {
+        Some((args.page.unwrap_or(1).max(1), args.page_size.unwrap_or(50).max(1)))
+    } else {
+        None
+    };
+
     let mut results = Vec::new();
+    let mut version_groups: Vec<CrateVersionGroup> = Vec::new();
     let mut warnings = Vec::new();
+    let mut has_more = false;
+
+    for item in &unknown_fetch {
+        warnings.push(Warning::new(None, "info", "unknown_fetch_component", format!("Ignoring unknown fetch component '{}'", item)));
+    }
+    for w in search_warnings {
+        warnings.push(Warning::new(None, "warning", "auto_select", format!("auto_select: {}", w)));
+    }
+    if !dropped_crates.is_empty() {
+        warnings.push(Warning::new(
+            None,
+            "warning",
+            "max_crates_exceeded",
+            format!(
+                "Only the first {} crates are looked up per call; skipped: {}",
+                max_crates,
+                dropped_crates.join(", ")
+            ),
+        ));
+    }
 
+    let flat_anchor_items = args.flat_anchor_items.unwrap_or(true);
+    // handles are awaited in spawn order, so results keep the input order whatever finishes first
     for h in handles {
-        if let Ok(res) = h.await {
-            if !res.errors.is_empty() {
-                for e in &res.errors {
-                    warnings.push(format!("{}: {}", res.name, e));
+        if ctx.ct.is_cancelled() {
+            h.abort();
+            continue;
+        }
+        if let Ok(Some(mut res)) = h.await {
+            if !flat_anchor_items {
+                res.docs_anchor_items.clear();
+            }
+            if let Some((page, page_size)) = pagination {
+                has_more |= paginate(&mut res.docs_anchor_items, page, page_size);
+                has_more |= paginate(&mut res.docs_items, page, page_size);
+                has_more |= paginate(&mut res.docs_code_snippets, page, page_size);
+                has_more |= paginate(&mut res.symbols, page, page_size);
+                has_more |= paginate(&mut res.function_signatures, page, page_size);
+            }
+            // computed here rather than at enrichment so cached results stay accurate
+            res.days_since_publish = res.published_at.as_deref().and_then(days_since);
+            if let Some(days) = res.days_since_publish.filter(|d| *d > STALE_VERSION_DAYS) {
+                warnings.push(Warning::new(
+                    Some(&res.name),
+                    "warning",
+                    "stale_version",
+                    format!(
+                        "latest version {} was published {} days ago; check whether the crate is still maintained or has a successor",
+                        res.latest_version, days
+                    ),
+                ));
+            }
+            if let Some(msg) = user_edition
+                .as_deref()
+                .zip(res.edition.as_deref())
+                .and_then(|(mine, theirs)| edition_incompatibility(theirs, mine))
+            {
+                warnings.push(Warning::new(Some(&res.name), "warning", "edition_too_new", msg));
+            }
+            let fatal = res.latest_version.is_empty();
+            for e in &res.errors {
+                warnings.push(Warning::from_crate_error(&res.name, e, fatal));
+            }
+            if multi_version.contains(&res.name) {
+                match version_groups.iter_mut().find(|g| g.name == res.name) {
+                    Some(group) => group.versions.push(res),
+                    None => version_groups.push(CrateVersionGroup { name: res.name.clone(), versions: vec![res] }),
                 }
+            } else {
+                results.push(res);
             }
-            results.push(res);
-        } else {
-            warnings.push("A background task failed while enriching a crate".to_string());
+        } else if !ctx.ct.is_cancelled() {
+            warnings.push(Warning::new(
+                None,
+                "error",
+                "task_failed",
+                "A background task failed while enriching a crate".to_string(),
+            ));
         }
     }
+    if ctx.ct.is_cancelled() {
+        tracing::info!("query_rustdocs cancelled by the client");
+        return Ok(CallToolResult::error(vec![Content::text("Request cancelled.")]));
+    }
 
-    let response = QueryRustDocsResponse {
+    let cargo_dependencies_block = cargo_dependencies_block(&results);
+    let mut response = QueryRustDocsResponse {
+        schema_version: SCHEMA_VERSION,
+        request_id: crate::request_id::current(),
         query_prompt: args.prompt,
+        generated_at: chrono::Utc::now().to_rfc3339(),
         // THIS HINT is intended to help LLM tool-using clients behave correctly.
-        tool_usage_hint: "IMPORTANT: this tool returns structured JSON only. The calling model must stop generation, parse this JSON, and then generate code using the returned `dependency_line`, `docs_rs_root`, `docs_code_snippets`, and `github_examples`. Do not append unrelated prose after calling this tool.".into(),
+        tool_usage_hint: "IMPORTANT: this tool returns structured JSON only. The calling model must stop generation, parse this JSON, and then generate code using the returned `cargo_dependencies_block` (or each crate's `dependency_line`), `docs_rs_root`, `docs_code_snippets`, and `github_examples`. Do not append unrelated prose after calling this tool.".into(),
+        cargo_dependencies_block,
+        version_groups,
         results,
-        warnings,
+        warnings: warnings.iter().map(Warning::flatten).collect(),
+        warning_details: warnings,
+        next_page: match pagination {
+            Some((page, _)) if has_more => Some(page + 1),
+            _ => None,
+        },
+        auto_selected,
     };
 
-    let payload = serde_json::to_string_pretty(&response)
-        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+    let payload = match max_response_bytes {
+        Some(max) => render_within_budget(&mut response, max, json_markers)?,
+        None => render_payload(&response, json_markers)?,
+    };
 
     Ok(CallToolResult::success(vec![Content::text(payload)]))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `responses` in order, one per connection, on a local port; returns the
+    /// base URL and a count of requests received.
+    async fn mock_server(responses: Vec<String>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        tokio::spawn(async move {
+            for resp in responses {
+                let Ok((mut sock, _)) = listener.accept().await else { return };
+                let mut req = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !req.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match sock.read(&mut buf).await {
+                        Ok(0) | Err(_) => break,
+                        Ok(n) => req.extend_from_slice(&buf[..n]),
+                    }
+                }
+                counter.fetch_add(1, Ordering::SeqCst);
+                let _ = sock.write_all(resp.as_bytes()).await;
+                let _ = sock.shutdown().await;
+            }
+        });
+        (base, hits)
+    }
+
+    const TOO_MANY: &str = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+    const OK: &str = "HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}";
+
+    fn json_response(body: &serde_json::Value) -> String {
+        let body = body.to_string();
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+    }
+
+    fn headers(retry_after: &str) -> reqwest::header::HeaderMap {
+        let mut h = reqwest::header::HeaderMap::new();
+        h.insert(reqwest::header::RETRY_AFTER, retry_after.parse().unwrap());
+        h
+    }
+
+    #[test]
+    fn retry_after_reads_delta_seconds() {
+        assert_eq!(retry_after_secs(&headers("7")), 7);
+        assert_eq!(retry_after_secs(&headers(" 0 ")), 0);
+    }
+
+    #[test]
+    fn retry_after_falls_back_for_dates_and_missing_header() {
+        assert_eq!(retry_after_secs(&headers("Wed, 21 Oct 2015 07:28:00 GMT")), 2);
+        assert_eq!(retry_after_secs(&reqwest::header::HeaderMap::new()), 2);
+    }
+
+    #[tokio::test]
+    async fn registry_get_retries_once_after_429() {
+        let (base, hits) = mock_server(vec![TOO_MANY.into(), OK.into()]).await;
+        let resp = registry_get(&Client::new(), &format!("{}/crates/serde", base), "test").await.unwrap();
+        assert_eq!(resp.status(), reqwest::StatusCode::OK);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn registry_get_reports_rate_limit_after_second_429() {
+        let (base, hits) = mock_server(vec![TOO_MANY.into(), TOO_MANY.into()]).await;
+        let err = registry_get(&Client::new(), &format!("{}/crates/serde", base), "test").await.unwrap_err();
+        assert!(matches!(err, RegistryError::RateLimited { retry_after_secs: 0 }));
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn registry_get_does_not_wait_out_long_retry_after() {
+        let slow = "HTTP/1.1 429 Too Many Requests\r\nRetry-After: 120\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+        let (base, hits) = mock_server(vec![slow.into()]).await;
+        let err = registry_get(&Client::new(), &format!("{}/crates/serde", base), "test").await.unwrap_err();
+        assert!(matches!(err, RegistryError::RateLimited { retry_after_secs: 120 }));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn next_page_joins_relative_query_to_listing_url() {
+        let body = serde_json::json!({ "meta": { "next_page": "?per_page=100&seek=abc" } });
+        assert_eq!(
+            registry_next_page("https://crates.io/api/v1/crates/serde/versions?per_page=100", &body).as_deref(),
+            Some("https://crates.io/api/v1/crates/serde/versions?per_page=100&seek=abc")
+        );
+    }
+
+    #[test]
+    fn next_page_keeps_absolute_urls_and_stops_when_absent() {
+        let absolute = serde_json::json!({ "meta": { "next_page": "https://crates.io/api/v1/crates/x/versions?seek=1" } });
+        assert_eq!(
+            registry_next_page("https://crates.io/api/v1/crates/x/versions", &absolute).as_deref(),
+            Some("https://crates.io/api/v1/crates/x/versions?seek=1")
+        );
+        for body in [
+            serde_json::json!({ "meta": { "next_page": null } }),
+            serde_json::json!({ "meta": { "next_page": "" } }),
+            serde_json::json!({ "versions": [] }),
+        ] {
+            assert_eq!(registry_next_page("https://crates.io/api/v1/crates/x/versions", &body), None);
+        }
+    }
+
+    #[tokio::test]
+    async fn best_version_reads_newest_release_from_page_two() {
+        let page_one = serde_json::json!({
+            "versions": [{ "num": "1.0.0", "yanked": false }],
+            "meta": { "next_page": "?per_page=1&seek=2" }
+        });
+        let page_two = serde_json::json!({
+            "versions": [{ "num": "2.0.0", "yanked": false }],
+            "meta": { "next_page": null }
+        });
+        let not_found = "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string();
+        let (base, hits) = mock_server(vec![json_response(&page_one), json_response(&page_two), not_found]).await;
+        let meta = fetch_crates_io_best_version(&Client::new(), &base, "demo", false).await.unwrap();
+        assert_eq!(meta.latest_version, "2.0.0");
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn version_pages_stop_at_the_cap() {
+        let page = serde_json::json!({
+            "versions": [{ "num": "1.0.0" }],
+            "meta": { "next_page": "?seek=more" }
+        });
+        let (base, hits) = mock_server(vec![json_response(&page); MAX_VERSION_PAGES + 5]).await;
+        let url = format!("{}/crates/demo/versions", base);
+        let versions = collect_version_pages(&Client::new(), &url, &page, "demo").await.unwrap();
+        assert_eq!(versions.len(), MAX_VERSION_PAGES);
+        assert_eq!(hits.load(Ordering::SeqCst), MAX_VERSION_PAGES - 1);
+    }
+
+    #[test]
+    fn short_statement_counts_as_rust() {
+        assert!(is_rust_like("let x = 5;", false));
+        assert!(is_rust_like("let x = 5;", true));
+    }
+
+    #[test]
+    fn prose_mentioning_keywords_is_not_rust() {
+        let prose = "Use this crate if you want to parse config files for the web.\nYou can use it from async code as well.";
+        assert!(rust_likeness_score(prose) < RUST_LIKENESS_THRESHOLD);
+        assert!(!is_rust_like(prose, false));
+    }
+
+    #[test]
+    fn code_only_rejects_incomplete_blocks() {
+        assert!(is_rust_like("fn main() {\n    println!(\"hi\");", false));
+        assert!(!is_rust_like("fn main() {\n    println!(\"hi\");", true));
+        assert!(is_rust_like("fn main() {\n    println!(\"hi\");\n}", true));
+    }
+
+    #[test]
+    fn delimiters_inside_char_literals_are_ignored() {
+        assert!(delimiters_balanced("let q = '\"';"));
+        assert!(delimiters_balanced("match c { '{' => 1, '(' => 2, _ => 0 }"));
+        assert!(delimiters_balanced("let esc = ['\\'', '\\\\', '\\u{7f}'];"));
+        assert!(is_rust_like("match c { '{' => 1, _ => 0 }", true));
+    }
+
+    #[test]
+    fn lifetimes_are_not_char_literals() {
+        assert!(delimiters_balanced("fn first<'a>(x: &'a str) -> &'a str { x }"));
+        assert!(delimiters_balanced("'outer: loop { break 'outer; }"));
+        assert!(!delimiters_balanced("fn f<'a>(x: &'a str) {"));
+    }
+
+    #[test]
+    fn docs_base_follows_redirect_to_hyphenated_crate() {
+        // docs.rs/tokio_util/latest/ redirects to the published name and resolved version
+        let final_url = "https://docs.rs/tokio-util/0.7.10/tokio_util/index.html";
+        assert_eq!(docs_base_from_url(final_url).as_deref(), Some("https://docs.rs/tokio-util/0.7.10/"));
+        assert_eq!(
+            docs_base_from_url("https://docs.rs/tokio-util/0.7.10/tokio_util/").as_deref(),
+            Some("https://docs.rs/tokio-util/0.7.10/")
+        );
+    }
+
+    #[test]
+    fn docs_base_rejects_non_docs_pages() {
+        assert_eq!(docs_base_from_url("https://docs.rs/crate/serde/1.0.0"), None);
+        assert_eq!(docs_base_from_url("https://docs.rs/serde"), None);
+        assert_eq!(docs_base_from_url("https://docs.rs/"), None);
+        assert_eq!(docs_base_from_url("not a url"), None);
+    }
+
+    #[test]
+    fn docs_hrefs_are_made_relative_to_the_redirected_base() {
+        let base = "https://docs.rs/tokio-util/0.7.10/";
+        let page = "https://docs.rs/tokio-util/0.7.10/tokio_util/index.html";
+        assert_eq!(
+            normalize_docs_href(page, "codec/struct.LinesCodec.html#method.new", base).as_deref(),
+            Some("tokio_util/codec/struct.LinesCodec.html")
+        );
+        assert_eq!(
+            normalize_docs_href(page, "sync/index.html?search=token", base).as_deref(),
+            Some("tokio_util/sync/index.html")
+        );
+        assert_eq!(normalize_docs_href(page, "../../../tokio/latest/tokio/index.html", base), None);
+        assert_eq!(normalize_docs_href(page, "https://github.com/tokio-rs/tokio", base), None);
+    }
+
+    /// The list fields of a fixed fixture, in the given order.
+    fn fixture(order: &[usize]) -> CrateResult {
+        let pick = |items: &[&str]| -> Vec<String> { order.iter().map(|&i| items[i].to_string()).collect() };
+        let paths = ["sync/struct.Mutex.html", "fn.spawn.html", "macro.select.html"];
+        let kinds = ["struct", "fn", "macro"];
+        CrateResult {
+            name: "tokio".to_string(),
+            latest_version: "1.0.0".to_string(),
+            docs_anchor_items: pick(&["Mutex", "spawn", "select"]).into_iter().chain(["spawn".to_string()]).collect(),
+            docs_items: order
+                .iter()
+                .map(|&i| DocItem { name: paths[i].to_string(), kind: kinds[i].to_string(), path: paths[i].to_string() })
+                .collect(),
+            symbols: pick(&["struct tokio::sync::Mutex", "fn tokio::spawn", "macro tokio::select"]),
+            function_signatures: pick(&["pub fn spawn<F>(f: F)", "pub fn block_on<F>(f: F)", "pub async fn sleep(d: Duration)"]),
+            item_docs: order
+                .iter()
+                .map(|&i| ItemDoc { path: paths[i].to_string(), doc_text: kinds[i].to_string() })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sorted_results_serialize_the_same_whatever_the_input_order() {
+        let mut a = fixture(&[0, 1, 2]);
+        let mut b = fixture(&[2, 0, 1]);
+        a.sort_lists();
+        b.sort_lists();
+        let a = serde_json::to_string_pretty(&a).unwrap();
+        assert_eq!(a, serde_json::to_string_pretty(&b).unwrap());
+
+        let v: serde_json::Value = serde_json::from_str(&a).unwrap();
+        assert_eq!(v["docs_anchor_items"], serde_json::json!(["Mutex", "select", "spawn"]));
+        assert_eq!(
+            v["docs_items"].as_array().unwrap().iter().map(|i| i["path"].as_str().unwrap()).collect::<Vec<_>>(),
+            ["fn.spawn.html", "macro.select.html", "sync/struct.Mutex.html"]
+        );
+        assert_eq!(
+            v["symbols"],
+            serde_json::json!(["fn tokio::spawn", "macro tokio::select", "struct tokio::sync::Mutex"])
+        );
+        assert_eq!(
+            v["function_signatures"],
+            serde_json::json!(["pub async fn sleep(d: Duration)", "pub fn block_on<F>(f: F)", "pub fn spawn<F>(f: F)"])
+        );
+        assert_eq!(
+            v["item_docs"].as_array().unwrap().iter().map(|i| i["path"].as_str().unwrap()).collect::<Vec<_>>(),
+            ["fn.spawn.html", "macro.select.html", "sync/struct.Mutex.html"]
+        );
+    }
+
+    #[test]
+    fn repo_paths_are_encoded_per_segment() {
+        assert_eq!(encode_path_segments("examples/echo.rs"), "examples/echo.rs");
+        assert_eq!(encode_path_segments("a b/c?ref=x#y"), "a%20b/c%3Fref%3Dx%23y");
+        assert_eq!(encode_path_segments("%2e%2e/x"), "%252e%252e/x");
+        assert_eq!(percent_encode("release/1.0"), "release%2F1.0");
+    }
+}
@@ -0,0 +1,214 @@
+// src/tools/recommend_crate.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::{Deserialize, Serialize};
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+use rmcp::serde_json;
+
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use std::future::Future;
+
+use crate::tools::query_rustdocs::{parse_registry_base, registry_get};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RecommendCrateArgs {
+    /// What the user wants to build, e.g. "parse command line arguments".
+    #[schemars(description = "Task description to find a crate for")]
+    task: String,
+
+    /// How many candidates to return (default 5, max 25).
+    #[serde(default)]
+    #[schemars(description = "Number of candidates to return (default 5)")]
+    limit: Option<usize>,
+
+    /// Leave out crates with fewer all-time downloads than this.
+    #[serde(default)]
+    #[schemars(description = "Minimum all-time downloads a candidate needs (default 0)", example = 10000)]
+    min_downloads: Option<u64>,
+}
+
+/// One crates.io search hit, scored for the recommendation.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrateCandidate {
+    pub name: String,
+    pub description: Option<String>,
+    pub latest_version: Option<String>,
+    pub dependency_line: Option<String>,
+    pub downloads: u64,
+    pub recent_downloads: u64,
+    pub updated_at: Option<String>,
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize)]
+struct RecommendCrateResponse {
+    task: String,
+    keywords: Vec<String>,
+    candidates: Vec<CrateCandidate>,
+    warnings: Vec<String>,
+}
+
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "the", "for", "with", "to", "of", "in", "on", "by", "from", "into", "that",
+    "this", "what", "which", "how", "want", "need", "use", "using", "crate", "crates", "rust",
+    "library", "lib", "some", "can", "should", "would", "like", "make", "build", "write", "program",
+    "app", "application", "project", "code", "best", "good", "simple", "easy", "way", "my", "me",
+];
+
+/// Lowercased search terms from a free-form task description (max 5, stopwords dropped).
+pub(crate) fn extract_keywords(text: &str) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for word in text.split(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_')) {
+        let w = word.trim_matches(|c| c == '-' || c == '_').to_lowercase();
+        if w.len() < 3 || STOPWORDS.contains(&w.as_str()) || out.contains(&w) {
+            continue;
+        }
+        out.push(w);
+        if out.len() >= 5 {
+            break;
+        }
+    }
+    out
+}
+
+/// Recent downloads (log-scaled) discounted by how long ago the crate was last updated.
+fn score_candidate(recent_downloads: u64, updated_at: Option<&str>, now: DateTime<Utc>) -> f64 {
+    let popularity = (1.0 + recent_downloads as f64).ln();
+    let years_stale = updated_at
+        .and_then(|u| DateTime::parse_from_rfc3339(u).ok())
+        .map(|u| (now - u.with_timezone(&Utc)).num_days().max(0) as f64 / 365.0)
+        .unwrap_or(5.0);
+    let recency = 1.0 / (1.0 + years_stale);
+    (popularity * recency * 1000.0).round() / 1000.0
+}
+
+/// Run one search against the registry at `registry_base` and turn the hits into scored candidates.
+pub(crate) async fn search_crates_io(
+    client: &Client,
+    registry_base: &str,
+    query: &str,
+    per_page: usize,
+) -> Result<Vec<CrateCandidate>, String> {
+    let per_page = per_page.to_string();
+    let url = reqwest::Url::parse_with_params(
+        &format!("{}/crates", registry_base),
+        [("q", query), ("per_page", per_page.as_str()), ("sort", "relevance")],
+    )
+    .map_err(|e| format!("invalid registry URL: {}", e))?;
+    let resp = registry_get(client, url.as_str(), &format!("crates.io search for '{}'", query))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if !resp.status().is_success() {
+        return Err(format!("crates.io search returned {} for '{}'", resp.status(), query));
+    }
+    let v: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("invalid JSON from crates.io search for '{}': {}", query, e))?;
+
+    let now = Utc::now();
+    let mut out = Vec::new();
+    for c in v.get("crates").and_then(|c| c.as_array()).into_iter().flatten() {
+        let Some(name) = c.get("name").and_then(|n| n.as_str()) else { continue };
+        let latest_version = c
+            .get("max_stable_version")
+            .and_then(|x| x.as_str())
+            .or_else(|| c.get("max_version").and_then(|x| x.as_str()))
+            .map(|s| s.to_string());
+        let recent_downloads = c.get("recent_downloads").and_then(|d| d.as_u64()).unwrap_or(0);
+        let updated_at = c.get("updated_at").and_then(|u| u.as_str()).map(|s| s.to_string());
+        out.push(CrateCandidate {
+            name: name.to_string(),
+            description: c
+                .get("description")
+                .and_then(|d| d.as_str())
+                .map(|d| d.split_whitespace().collect::<Vec<_>>().join(" ")),
+            dependency_line: latest_version.as_ref().map(|v| format!(r#"{} = "{}""#, name, v)),
+            latest_version,
+            downloads: c.get("downloads").and_then(|d| d.as_u64()).unwrap_or(0),
+            recent_downloads,
+            score: score_candidate(recent_downloads, updated_at.as_deref(), now),
+            updated_at,
+        });
+    }
+    Ok(out)
+}
+
+/// Search for the whole keyword phrase, then each keyword alone, merge by name
+/// and rank by score (ties broken by name so the order is stable). Crates under
+/// `min_downloads` all-time downloads are dropped before the cut to `limit`.
+pub(crate) async fn recommend(
+    client: &Client,
+    registry_base: &str,
+    keywords: &[String],
+    limit: usize,
+    min_downloads: u64,
+) -> (Vec<CrateCandidate>, Vec<String>) {
+    let mut queries = Vec::new();
+    if keywords.len() > 1 {
+        queries.push(keywords.join(" "));
+    }
+    queries.extend(keywords.iter().cloned());
+
+    let mut merged: Vec<CrateCandidate> = Vec::new();
+    let mut warnings = Vec::new();
+    for q in &queries {
+        match search_crates_io(client, registry_base, q, 20).await {
+            Ok(hits) => {
+                for hit in hits {
+                    if !merged.iter().any(|m| m.name == hit.name) {
+                        merged.push(hit);
+                    }
+                }
+            }
+            Err(e) => warnings.push(e),
+        }
+    }
+    merged.retain(|c| c.downloads >= min_downloads);
+    merged.sort_by(|a, b| b.score.total_cmp(&a.score).then_with(|| a.name.cmp(&b.name)));
+    merged.truncate(limit);
+    (merged, warnings)
+}
+
+#[tool(
+    name = "recommend_crate",
+    description = "Searches crates.io for a described task and returns a ranked shortlist of crates scored by recent downloads and how recently they were updated."
+)]
+pub async fn recommend_crate(
+    Parameters(args): Parameters<RecommendCrateArgs>,
+    client: Client,
+) -> Result<CallToolResult, ErrorData> {
+    let keywords = extract_keywords(&args.task);
+    if keywords.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text(
+            "Could not derive any search keywords from the task description.",
+        )]));
+    }
+
+    let registry_base = match parse_registry_base(&crate::config::config().registry_base) {
+        Ok(b) => b,
+        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+    };
+
+    let limit = args.limit.unwrap_or(5).clamp(1, 25);
+    let (candidates, warnings) =
+        recommend(&client, &registry_base, &keywords, limit, args.min_downloads.unwrap_or(0)).await;
+
+    let response = RecommendCrateResponse {
+        task: args.task,
+        keywords,
+        candidates,
+        warnings,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}
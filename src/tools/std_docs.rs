@@ -0,0 +1,106 @@
+// src/tools/std_docs.rs
+
+use rmcp::tool;
+use rmcp::handler::server::tool::Parameters;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::Deserialize;
+use rmcp::schemars::JsonSchema;
+use rmcp::schemars;
+
+use std::future::Future;
+use std::time::Duration;
+
+use crate::tools::docs_to_markdown::html_to_markdown;
+use crate::tools::query_rustdocs::{fetch_compressed_text, item_path_candidates};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct StdDocsArgs {
+    /// Path of a standard library item or module, e.g. "std::collections::HashMap",
+    /// "core::option::Option", "std::fs" or "i32". Paths without a std/core/alloc/proc_macro
+    /// root are looked up in std.
+    #[schemars(example = "std::collections::HashMap")]
+    path: String,
+
+    /// "stable" (default), "beta", "nightly" or a release like "1.80.0".
+    #[serde(default)]
+    #[schemars(example = "stable")]
+    channel: Option<String>,
+}
+
+const STD_DOCS_BASE: &str = "https://doc.rust-lang.org";
+
+/// Crates documented under doc.rust-lang.org.
+const STD_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro"];
+
+/// Item kinds tried, in order, before falling back to a module page.
+const ITEM_KINDS: &[&str] = &[
+    "struct", "enum", "trait", "fn", "macro", "type", "constant", "static", "union", "primitive", "keyword",
+];
+
+fn valid_channel(channel: &str) -> bool {
+    matches!(channel, "stable" | "beta" | "nightly")
+        || (channel.split('.').count() == 3 && channel.split('.').all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit())))
+}
+
+#[tool(
+    name = "std_docs",
+    description = "Fetches the documentation page of a standard library item or module (e.g. \"std::collections::HashMap\", \"core::option::Option\", \"std::fs\") from doc.rust-lang.org and returns it as markdown. Supports the stable, beta and nightly channels. Use it for std/core/alloc, which query_rustdocs does not cover."
+)]
+pub async fn std_docs(
+    Parameters(args): Parameters<StdDocsArgs>,
+) -> Result<CallToolResult, ErrorData> {
+    let channel = args.channel.as_deref().map(str::trim).filter(|c| !c.is_empty()).unwrap_or("stable").to_lowercase();
+    if !valid_channel(&channel) {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "Unknown channel '{}'. Use \"stable\", \"beta\", \"nightly\" or a release like \"1.80.0\".",
+            channel
+        ))]));
+    }
+    let path = args.path.trim().trim_matches(':');
+    if path.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text("path is required.")]));
+    }
+    let mut segments: Vec<&str> = path.split("::").filter(|s| !s.is_empty()).collect();
+    let krate = match segments.first() {
+        Some(first) if STD_CRATES.contains(first) => segments.remove(0),
+        _ => "std",
+    };
+
+    let mut candidates = if segments.is_empty() {
+        Vec::new()
+    } else {
+        item_path_candidates(krate, &segments.join("::"), ITEM_KINDS)
+    };
+    let mut module = krate.to_string();
+    for s in &segments {
+        module.push('/');
+        module.push_str(s);
+    }
+    candidates.push(format!("{}/index.html", module));
+
+    let delay = Duration::from_millis(crate::config::config().crawl_delay_ms);
+    let mut page = None;
+    for cand in &candidates {
+        let url = format!("{}/{}/{}", STD_DOCS_BASE, channel, cand);
+        if let Some(found) = fetch_compressed_text(&url, delay).await {
+            page = Some(found);
+            break;
+        }
+    }
+    let Some((final_url, html)) = page else {
+        return Ok(CallToolResult::error(vec![Content::text(format!(
+            "No {} docs page found for '{}' on {} (tried {} paths). For a method, pass its type, e.g. \"std::vec::Vec\".",
+            krate,
+            path,
+            channel,
+            candidates.len()
+        ))]));
+    };
+    let markdown = html_to_markdown(&html, &final_url);
+    if markdown.is_empty() {
+        return Ok(CallToolResult::error(vec![Content::text(format!("No content found on {}.", final_url))]));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(format!("<!-- source: {} -->\n\n{}", final_url, markdown))]))
+}
@@ -0,0 +1,43 @@
+// src/tools/version.rs
+
+use rmcp::tool;
+use rmcp::model::{CallToolResult, Content};
+use rmcp::ErrorData;
+use serde::Serialize;
+use rmcp::serde_json;
+
+use std::future::Future;
+
+#[derive(Debug, Serialize)]
+struct VersionResponse {
+    name: &'static str,
+    version: &'static str,
+    git_commit: &'static str,
+    /// RFC 3339, from the build script's unix timestamp.
+    built_at: Option<String>,
+}
+
+#[tool(
+    name = "version",
+    description = "Returns the server's crate version, the git commit it was built from, and the build time. Include it when reporting bugs."
+)]
+pub async fn version() -> Result<CallToolResult, ErrorData> {
+    let built_at = env!("BUILD_TIMESTAMP")
+        .parse::<i64>()
+        .ok()
+        .filter(|t| *t > 0)
+        .and_then(|t| chrono::DateTime::from_timestamp(t, 0))
+        .map(|t| t.to_rfc3339());
+
+    let response = VersionResponse {
+        name: env!("CARGO_PKG_NAME"),
+        version: env!("CARGO_PKG_VERSION"),
+        git_commit: env!("GIT_HASH"),
+        built_at,
+    };
+
+    let payload = serde_json::to_string_pretty(&response)
+        .map_err(|e| ErrorData::internal_error(format!("serializing response failed: {}", e), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(payload)]))
+}